@@ -0,0 +1,62 @@
+use crate::transform::Transform;
+
+/// Maps world space, where primitives are placed, to screen pixels.
+/// `offset` is the world point drawn at the top left of the screen, and `zoom` and `rotation`
+/// turn and scale the world around it.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Camera {
+    pub offset: [f32; 2],
+    pub zoom: f32,
+    pub rotation: f32
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self { offset: [0.0, 0.0], zoom: 1.0, rotation: 0.0 }
+    }
+}
+
+impl Camera {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// World to screen.
+    pub fn view_transform(&self) -> Transform {
+        Transform::translation([-self.offset[0], -self.offset[1]])
+            .then(&Transform::scale([self.zoom, self.zoom]))
+            .then(&Transform::rotation(self.rotation))
+    }
+
+    pub fn world_to_screen(&self, point: [f32; 2]) -> [f32; 2] {
+        self.view_transform().apply(point)
+    }
+
+    /// `None` while the zoom is zero.
+    pub fn screen_to_world(&self, point: [f32; 2]) -> Option<[f32; 2]> {
+        self.view_transform().inverse().map(|inverse| inverse.apply(point))
+    }
+
+    /// Moves the view by `delta` screen pixels, so the world follows a dragging cursor.
+    pub fn pan(&mut self, delta: [f32; 2]) {
+        if let Some(inverse) = self.view_transform().inverse() {
+            let world_delta = inverse.apply_vector(delta);
+            self.offset = [self.offset[0] - world_delta[0], self.offset[1] - world_delta[1]];
+        }
+    }
+
+    /// Multiplies the zoom by `factor`, keeping the world point under `screen_point` where it is.
+    pub fn zoom_at(&mut self, factor: f32, screen_point: [f32; 2]) {
+        let anchor = match self.screen_to_world(screen_point) {
+            Some(anchor) => anchor,
+            None => return
+        };
+        self.zoom *= factor;
+        if let Some(moved) = self.screen_to_world(screen_point) {
+            self.offset = [
+                self.offset[0] + anchor[0] - moved[0],
+                self.offset[1] + anchor[1] - moved[1]
+            ];
+        }
+    }
+}
@@ -0,0 +1,551 @@
+use crate::font::FontAtlas;
+use crate::gradient::{Gradient, GRADIENT_RAMP_SIZE};
+use crate::primitives::RenderPrimitive;
+use crate::transform::Transform;
+use crate::vector::{cross, dot, length, normalise, subtract};
+
+// Mirrors the fragment shader in `sdf_renderer`, keep the two in step
+
+const JOIN_OVERLAP: f32 = 1.0 / 64.0;
+const JOIN_ANGLE_OVERLAP: f32 = 1.0 / 1024.0;
+
+pub fn median(r: f32, g: f32, b: f32) -> f32 {
+    r.min(g).max(r.max(g).min(b))
+}
+
+pub fn circle_signed_dist(position: [f32; 2], radius: f32) -> f32 {
+    length(position) - radius
+}
+
+/// `position` from the centre and `corner_radii` in pixels.
+pub fn box_signed_dist(position: [f32; 2], half_size: [f32; 2], corner_radii: [f32; 4]) -> f32 {
+    let corner_radius_index = step(0.0, position[0]) as usize + step(0.0, position[1]) as usize * 2;
+    let corner_radius = corner_radii[corner_radius_index];
+
+    let offset = [
+        position[0].abs() - half_size[0] + corner_radius,
+        position[1].abs() - half_size[1] + corner_radius
+    ];
+    offset[0].max(offset[1]).min(0.0) + length([offset[0].max(0.0), offset[1].max(0.0)]) - corner_radius
+}
+
+/// Close to the true distance near the outline, as in the shader.
+pub fn ellipse_signed_dist(position: [f32; 2], radii: [f32; 2]) -> f32 {
+    let scaled = length([position[0] / radii[0], position[1] / radii[1]]);
+    let gradient = length([position[0] / (radii[0] * radii[0]), position[1] / (radii[1] * radii[1])]);
+    if gradient > 0.0 { scaled * (scaled - 1.0) / gradient } else { -radii[0].min(radii[1]) }
+}
+
+// Turned so the slice opens along x, then mirrored onto its lower half
+fn slice_position(position: [f32; 2], direction: [f32; 2]) -> [f32; 2] {
+    [dot(position, direction), cross(direction, position).abs()]
+}
+
+/// `direction` points through the middle of the slice, and `aperture` holds the cosine and sine
+/// of half its angle.
+pub fn pie_signed_dist(position: [f32; 2], direction: [f32; 2], aperture: [f32; 2], radius: f32) -> f32 {
+    let p = slice_position(position, direction);
+    let outside_circle = length(p) - radius;
+    let along_side = dot(p, aperture).clamp(0.0, radius);
+    let to_side = length([p[0] - aperture[0] * along_side, p[1] - aperture[1] * along_side]);
+    outside_circle.max(to_side * sign(cross(aperture, p)))
+}
+
+pub fn arc_signed_dist(position: [f32; 2], direction: [f32; 2], aperture: [f32; 2], radius: f32, thickness: f32) -> f32 {
+    let p = slice_position(position, direction);
+    let centre_radius = radius - 0.5 * thickness;
+    let to_centre_line = if aperture[0] * p[1] > aperture[1] * p[0] {
+        length([p[0] - aperture[0] * centre_radius, p[1] - aperture[1] * centre_radius])
+    } else {
+        (length(p) - centre_radius).abs()
+    };
+    to_centre_line - 0.5 * thickness
+}
+
+pub fn triangle_signed_dist(position: [f32; 2], vertices: [[f32; 2]; 3]) -> f32 {
+    let winding = sign(cross(subtract(vertices[1], vertices[0]), subtract(vertices[0], vertices[2])));
+    let mut nearest = [f32::INFINITY, f32::INFINITY];
+    for index in 0..3 {
+        let start = vertices[index];
+        let edge = subtract(vertices[(index + 1) % 3], start);
+        let from_start = subtract(position, start);
+        let along = (dot(from_start, edge) / dot(edge, edge).max(1e-6)).clamp(0.0, 1.0);
+        let to_edge = [from_start[0] - edge[0] * along, from_start[1] - edge[1] * along];
+        // GLSL's component-wise min
+        nearest[0] = nearest[0].min(dot(to_edge, to_edge));
+        nearest[1] = nearest[1].min(winding * cross(from_start, edge));
+    }
+    -nearest[0].sqrt() * sign(nearest[1])
+}
+
+/// A regular polygon with a corner pointing up and its corners `radius` from the centre.
+pub fn polygon_signed_dist(position: [f32; 2], radius: f32, sides: f32) -> f32 {
+    let sector = std::f32::consts::TAU / sides;
+    let first_edge = 0.5 * sector - std::f32::consts::FRAC_PI_2;
+    let edge_angle = first_edge + sector * ((position[1].atan2(position[0]) - first_edge) / sector + 0.5).floor();
+    let edge_direction = [edge_angle.cos(), edge_angle.sin()];
+    let p = [dot(position, edge_direction), cross(edge_direction, position)];
+
+    let apothem = radius * (0.5 * sector).cos();
+    let half_edge = radius * (0.5 * sector).sin();
+    length([p[0] - apothem, p[1] - p[1].clamp(-half_edge, half_edge)]) * sign(p[0] - apothem)
+}
+
+fn edge_signed_dist(position: [f32; 2], from: [f32; 2], to: [f32; 2], winding: f32) -> f32 {
+    let edge = subtract(to, from);
+    -winding * cross(edge, subtract(position, from)) / length(edge)
+}
+
+/// Inside the edges of a path triangle on the path's outline, marked by the bits of `outline`.
+/// Edges shared with the path's other triangles don't fade.
+pub fn outline_signed_dist(position: [f32; 2], vertices: [[f32; 2]; 3], outline: f32) -> f32 {
+    let [a, b, c] = vertices;
+    let winding = sign(cross(subtract(b, a), subtract(c, a)));
+    let mut dist = -1e6f32;
+    if outline % 2.0 >= 1.0 {
+        dist = dist.max(edge_signed_dist(position, a, b, winding));
+    }
+    if outline % 4.0 >= 2.0 {
+        dist = dist.max(edge_signed_dist(position, b, c, winding));
+    }
+    if outline >= 4.0 {
+        dist = dist.max(edge_signed_dist(position, c, a, winding));
+    }
+    dist
+}
+
+/// A gradient's colour from its row of the gradient table, see `Gradient::texels`.
+pub fn gradient_colour(texels: &[[f32; 4]], texture_coord: [f32; 2], dimensions: [f32; 2]) -> [f32; 4] {
+    let [kind, centre_x, centre_y, parameter] = texels[0];
+    let position = [(texture_coord[0] - centre_x) * dimensions[0], (texture_coord[1] - centre_y) * dimensions[1]];
+    let offset = if kind < 0.5 {
+        let direction = [parameter.cos(), parameter.sin()];
+        dot(position, direction) / dot([direction[0].abs(), direction[1].abs()], dimensions) + 0.5
+    } else if kind < 1.5 {
+        length([(texture_coord[0] - centre_x) / parameter, (texture_coord[1] - centre_y) / parameter])
+    } else {
+        let turns = (position[1].atan2(position[0]) - parameter) / std::f32::consts::TAU;
+        turns - turns.floor()
+    };
+
+    let ramp_position = offset.clamp(0.0, 1.0) * (GRADIENT_RAMP_SIZE - 1) as f32;
+    let index = (ramp_position as usize).min(GRADIENT_RAMP_SIZE - 2);
+    mix(texels[1 + index], texels[2 + index], ramp_position - index as f32)
+}
+
+/// Past the end of a stroke, with x running outward from the end: butt, square and round caps.
+pub fn cap_signed_dist(position: [f32; 2], cap: f32, half_width: f32) -> f32 {
+    if cap < 0.5 {
+        (position[1].abs() - half_width).max(position[0])
+    } else if cap < 1.5 {
+        (position[1].abs() - half_width).max(position[0] - half_width)
+    } else {
+        length([position[0].max(0.0), position[1]]) - half_width
+    }
+}
+
+/// Past the end of a stroke segment: the caps, then miter, bevel and round joins. Joins stop
+/// `seam` past the plane with `normal` through the end, where the next segment takes over.
+pub fn segment_end_signed_dist(position: [f32; 2], normal: [f32; 2], end: f32, half_width: f32, seam: f32) -> f32 {
+    if end < 2.5 {
+        return cap_signed_dist(position, end, half_width);
+    }
+    if dot(position, normal) > seam + JOIN_ANGLE_OVERLAP * length(position) {
+        return 1e6;
+    }
+    if end > 4.5 {
+        cap_signed_dist(position, 2.0, half_width)
+    } else if end > 3.5 {
+        // Across the corner from the outer edge's end, on the side the plane leans away from
+        let side = if normal[1] > 0.0 { -1.0 } else { 1.0 };
+        let corner = [0.0, side * half_width];
+        dot(subtract(position, corner), [-side * normal[1], side * normal[0]])
+    } else {
+        -1e6
+    }
+}
+
+/// Distance to the nearest point of a quadratic Bezier curve and where along it that is.
+pub fn bezier_nearest(position: [f32; 2], from: [f32; 2], control: [f32; 2], to: [f32; 2]) -> [f32; 2] {
+    let a = subtract(control, from);
+    let b = [from[0] - 2.0 * control[0] + to[0], from[1] - 2.0 * control[1] + to[1]];
+    let d = subtract(from, position);
+    let distance_at = |t: f32| length([d[0] + (2.0 * a[0] + b[0] * t) * t, d[1] + (2.0 * a[1] + b[1] * t) * t]);
+    if dot(b, b) < 1e-4 {
+        let chord = subtract(to, from);
+        let t = (-dot(d, chord) / dot(chord, chord).max(1e-6)).clamp(0.0, 1.0);
+        return [length([d[0] + chord[0] * t, d[1] + chord[1] * t]), t];
+    }
+
+    let kk = 1.0 / dot(b, b);
+    let kx = kk * dot(a, b);
+    let ky = kk * (2.0 * dot(a, a) + dot(d, b)) / 3.0;
+    let kz = kk * dot(d, a);
+    let p = ky - kx * kx;
+    let q = kx * (2.0 * kx * kx - 3.0 * ky) + kz;
+    let h = q * q + 4.0 * p * p * p;
+    if h >= 0.0 {
+        let h = h.sqrt();
+        let roots = [((h - q) / 2.0).cbrt(), ((-h - q) / 2.0).cbrt()];
+        let t = (roots[0] + roots[1] - kx).clamp(0.0, 1.0);
+        return [distance_at(t), t];
+    }
+    let z = (-p).sqrt();
+    let v = (q / (p * z * 2.0)).acos() / 3.0;
+    let m = v.cos();
+    let n = v.sin() * 3.0f32.sqrt();
+    let first = ((m + m) * z - kx).clamp(0.0, 1.0);
+    let second = ((-n - m) * z - kx).clamp(0.0, 1.0);
+    if distance_at(first) < distance_at(second) { [distance_at(first), first] } else { [distance_at(second), second] }
+}
+
+// `position` from the end of a stroke, turned so x runs along `outward`
+fn end_position(position: [f32; 2], end: [f32; 2], outward: [f32; 2]) -> [f32; 2] {
+    let from_end = subtract(position, end);
+    [dot(from_end, outward), cross(outward, from_end)]
+}
+
+// Past the ends only the caps are drawn, and just inside them the caps fade the edge
+fn with_cap(dist: f32, position: [f32; 2], cap: f32, half_width: f32, past_end: bool) -> f32 {
+    let cap_dist = cap_signed_dist(position, cap, half_width);
+    if past_end {
+        cap_dist
+    } else if position[0] > -half_width && position[1].abs() < half_width {
+        dist.max(cap_dist)
+    } else {
+        dist
+    }
+}
+
+/// GLSL `smoothstep`, including the reversed edges the shader relies on.
+pub fn smoothstep(edge0: f32, edge1: f32, x: f32) -> f32 {
+    if edge0 == edge1 {
+        return step(edge0, x);
+    }
+    let t = ((x - edge0) / (edge1 - edge0)).clamp(0.0, 1.0);
+    t * t * (3.0 - 2.0 * t)
+}
+
+fn step(edge: f32, x: f32) -> f32 {
+    if x < edge { 0.0 } else { 1.0 }
+}
+
+// GLSL `sign`, zero at zero
+fn sign(value: f32) -> f32 {
+    if value > 0.0 { 1.0 } else if value < 0.0 { -1.0 } else { 0.0 }
+}
+
+fn mix<const N: usize>(from: [f32; N], to: [f32; N], amount: f32) -> [f32; N] {
+    let mut mixed = from;
+    for (channel, target) in mixed.iter_mut().zip(to.iter()) {
+        *channel += (target - *channel) * amount;
+    }
+    mixed
+}
+
+fn stroke_and_fill(dist: f32, smoothness: f32, stroke_width: f32, inner_colour: [f32; 4], outer_colour: [f32; 4]) -> ([f32; 3], f32) {
+    let outer = smoothstep(smoothness, -smoothness, dist);
+    let inner = smoothstep(-stroke_width + smoothness, -stroke_width - smoothness, dist);
+    let alpha = smoothstep(0.0, -smoothness, dist);
+    let colour = mix(outer_colour, inner_colour, inner);
+    ([colour[0] * outer, colour[1] * outer, colour[2] * outer], colour[3] * alpha)
+}
+
+/// Rasterises primitive lists into images without a GL context, using the same
+/// maths as the SDF fragment shader.
+pub struct CpuRenderer {
+    glyphs: Vec<image::RgbaImage>,
+    gradients: Vec<Vec<[f32; 4]>>,
+    scale_factor: f32
+}
+
+impl Default for CpuRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl CpuRenderer {
+    pub fn new() -> Self {
+        CpuRenderer::with_font_atlas(FontAtlas::segoe_ui())
+    }
+
+    pub fn with_font_atlas(font_atlas: FontAtlas) -> Self {
+        Self {
+            glyphs: font_atlas.layers,
+            gradients: vec!(),
+            scale_factor: 1.0
+        }
+    }
+
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    /// Like `SdfRenderer::set_scale_factor`, primitives are in logical units and `render`'s
+    /// size in physical pixels.
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.scale_factor = scale_factor as f32;
+    }
+
+    /// Like `SdfRenderer::set_gradients`.
+    pub fn set_gradients(&mut self, gradients: &[Gradient]) {
+        self.gradients = gradients.iter().map(Gradient::texels).collect();
+    }
+
+    pub fn render(&self, primitives: &[RenderPrimitive], width: u32, height: u32, clear_colour: [f32; 4]) -> image::RgbaImage {
+        let mut target = vec![clear_colour; (width * height) as usize];
+        let scale = Transform::scale([self.scale_factor, self.scale_factor]);
+        for primitive in primitives {
+            // Round trip through the vertex encoding so colours and payloads match the GPU's
+            self.rasterise(&primitive.pack().unpack().transformed(scale), &mut target, width, height);
+        }
+
+        image::RgbaImage::from_fn(width, height, |x, y| {
+            let pixel = target[(y * width + x) as usize];
+            image::Rgba([to_u8(pixel[0]), to_u8(pixel[1]), to_u8(pixel[2]), to_u8(pixel[3])])
+        })
+    }
+
+    fn rasterise(&self, primitive: &RenderPrimitive, target: &mut [[f32; 4]], width: u32, height: u32) {
+        let dimensions = primitive.dimensions;
+        if dimensions[0] <= 0.0 || dimensions[1] <= 0.0 {
+            return;
+        }
+        let inverse = match primitive.transform.inverse() {
+            Some(inverse) => inverse,
+            None => return
+        };
+
+        let left = primitive.position[0] - dimensions[0] / 2.0;
+        let top = primitive.position[1] - dimensions[1] / 2.0;
+        // Path triangles cover only themselves rather than their whole quad
+        let triangle = path_triangle_vertices(primitive);
+        let corners: Vec<[f32; 2]> = match triangle {
+            Some(vertices) => vertices.iter()
+                .map(|vertex| primitive.transform.apply([primitive.position[0] + vertex[0], primitive.position[1] + vertex[1]]))
+                .collect(),
+            None => vec!(
+                primitive.transform.apply([left, top]),
+                primitive.transform.apply([left + dimensions[0], top]),
+                primitive.transform.apply([left, top + dimensions[1]]),
+                primitive.transform.apply([left + dimensions[0], top + dimensions[1]])
+            )
+        };
+        let min = |axis: usize| corners.iter().map(|corner| corner[axis]).fold(f32::INFINITY, f32::min);
+        let max = |axis: usize| corners.iter().map(|corner| corner[axis]).fold(f32::NEG_INFINITY, f32::max);
+
+        // How far one screen pixel moves through the quad, for fwidth
+        let step_x = inverse.apply_vector([1.0, 0.0]);
+        let step_y = inverse.apply_vector([0.0, 1.0]);
+        let texture_steps = [
+            [step_x[0] / dimensions[0], step_x[1] / dimensions[1]],
+            [step_y[0] / dimensions[0], step_y[1] / dimensions[1]]
+        ];
+
+        for y in pixel_range(min(1), max(1), height) {
+            for x in pixel_range(min(0), max(0), width) {
+                // Pixels are covered when their centre falls inside the quad
+                let local = inverse.apply([x as f32 + 0.5, y as f32 + 0.5]);
+                let texture_coord = [(local[0] - left) / dimensions[0], (local[1] - top) / dimensions[1]];
+                let covered = match triangle {
+                    Some(vertices) => covers_triangle(subtract(local, primitive.position), vertices),
+                    None => (0.0..1.0).contains(&texture_coord[0]) && (0.0..1.0).contains(&texture_coord[1])
+                };
+                if !covered {
+                    continue;
+                }
+
+                let (colour, alpha) = self.shade(primitive, texture_coord, texture_steps);
+                let destination = &mut target[(y * width + x) as usize];
+                for channel in 0..3 {
+                    destination[channel] = colour[channel] * alpha + destination[channel] * (1.0 - alpha);
+                }
+                destination[3] = alpha * alpha + destination[3] * (1.0 - alpha);
+            }
+        }
+    }
+
+    fn shade(&self, primitive: &RenderPrimitive, texture_coord: [f32; 2], texture_steps: [[f32; 2]; 2]) -> ([f32; 3], f32) {
+        let mut inner_colour = primitive.inner_colour;
+        let mut outer_colour = primitive.outer_colour;
+        let stroke_width = primitive.extra_data_1[0];
+        let dimensions = primitive.dimensions;
+        let gradient = (primitive.identification[1] as usize).checked_sub(1).and_then(|index| self.gradients.get(index));
+        if let Some(texels) = gradient.filter(|_| primitive.identification[0] != 2) {
+            inner_colour = gradient_colour(texels, texture_coord, dimensions);
+            if primitive.identification[0] >= 7 || stroke_width <= 0.0 {
+                outer_colour = inner_colour;
+            }
+        }
+        let short_side = dimensions[0].min(dimensions[1]);
+        let pixel_position = |texture_coord: [f32; 2]| [(texture_coord[0] - 0.5) * dimensions[0], (texture_coord[1] - 0.5) * dimensions[1]];
+
+        // The shader's dFdx and dFdy of `pixel_position`
+        let pixel_size = 0.5 * (
+            length([texture_steps[0][0] * dimensions[0], texture_steps[0][1] * dimensions[1]]) +
+            length([texture_steps[1][0] * dimensions[0], texture_steps[1][1] * dimensions[1]])
+        );
+        let smoothness = 0.5 * pixel_size * self.scale_factor;
+
+        match primitive.identification[0] {
+            0 => {
+                let dist = circle_signed_dist(pixel_position(texture_coord), 0.5 * short_side);
+                stroke_and_fill(dist, smoothness, stroke_width, inner_colour, outer_colour)
+            },
+            1 => {
+                let corner_radii = primitive.extra_data_2.map(|radius| radius * short_side);
+                let half_size = [0.5 * dimensions[0], 0.5 * dimensions[1]];
+                let dist = box_signed_dist(pixel_position(texture_coord), half_size, corner_radii);
+                stroke_and_fill(dist, smoothness, stroke_width, inner_colour, outer_colour)
+            },
+            2 => {
+                // An atlas without glyphs has nothing to sample, so text stays transparent
+                if self.glyphs.is_empty() {
+                    return ([0.0, 0.0, 0.0], 0.0);
+                }
+                let glyph_index = primitive.identification[1];
+                let (dist, width) = fwidth(|texture_coord| self.glyph_dist(glyph_index, texture_coord), texture_coord, texture_steps);
+                let width = width * self.scale_factor;
+                ([outer_colour[0], outer_colour[1], outer_colour[2]], smoothstep(0.5 - width, 0.5 + width, dist) * outer_colour[3])
+            },
+            3 => {
+                let dist = ellipse_signed_dist(pixel_position(texture_coord), [0.5 * dimensions[0], 0.5 * dimensions[1]]);
+                stroke_and_fill(dist, smoothness, stroke_width, inner_colour, outer_colour)
+            },
+            4 => {
+                let data = primitive.extra_data_2;
+                let thickness = primitive.extra_data_1[1];
+                let dist = if thickness > 0.0 {
+                    arc_signed_dist(pixel_position(texture_coord), [data[0], data[1]], [data[2], data[3]], 0.5 * short_side, thickness)
+                } else {
+                    pie_signed_dist(pixel_position(texture_coord), [data[0], data[1]], [data[2], data[3]], 0.5 * short_side)
+                };
+                stroke_and_fill(dist, smoothness, stroke_width, inner_colour, outer_colour)
+            },
+            5 => {
+                let scale = |vertex: [f32; 2]| [vertex[0] * dimensions[0], vertex[1] * dimensions[1]];
+                let vertices = [
+                    scale([primitive.extra_data_2[0], primitive.extra_data_2[1]]),
+                    scale([primitive.extra_data_2[2], primitive.extra_data_2[3]]),
+                    scale([primitive.extra_data_1[1], primitive.extra_data_1[2]])
+                ];
+                let dist = triangle_signed_dist(pixel_position(texture_coord), vertices);
+                stroke_and_fill(dist, smoothness, stroke_width, inner_colour, outer_colour)
+            },
+            6 => {
+                let dist = polygon_signed_dist(pixel_position(texture_coord), 0.5 * short_side, primitive.extra_data_1[1]);
+                stroke_and_fill(dist, smoothness, stroke_width, inner_colour, outer_colour)
+            },
+            7 => {
+                let half_width = 0.5 * primitive.extra_data_1[0];
+                let scale = |point: [f32; 2]| [point[0] * dimensions[0], point[1] * dimensions[1]];
+                let from = scale([primitive.extra_data_1[1], primitive.extra_data_1[2]]);
+                let control = scale([primitive.extra_data_2[0], primitive.extra_data_2[1]]);
+                let to = scale([primitive.extra_data_2[2], primitive.extra_data_2[3]]);
+                let caps = primitive.extra_data_1[3];
+                let position = pixel_position(texture_coord);
+
+                let nearest = bezier_nearest(position, from, control, to);
+                let start_outward = normalise(subtract(from, if control == from { to } else { control }));
+                let end_outward = normalise(subtract(to, if control == to { from } else { control }));
+                let dist = nearest[0] - half_width;
+                let dist = with_cap(dist, end_position(position, from, start_outward), caps % 4.0, half_width, nearest[1] <= 0.0);
+                let dist = with_cap(dist, end_position(position, to, end_outward), (caps / 4.0).floor(), half_width, nearest[1] >= 1.0);
+                stroke_and_fill(dist, smoothness, 0.0, inner_colour, outer_colour)
+            },
+            8 => {
+                let half_width = 0.5 * dimensions[1];
+                let half_length = 0.5 * dimensions[0] - primitive.extra_data_1[0];
+                let [start_angle, end_angle] = [primitive.extra_data_1[1], primitive.extra_data_1[2]];
+                let position = pixel_position(texture_coord);
+                let from_start = [-position[0] - half_length, position[1]];
+                let from_end = [position[0] - half_length, position[1]];
+
+                let dist = (position[1].abs() - half_width)
+                    .max(segment_end_signed_dist(from_start, [start_angle.cos(), start_angle.sin()], primitive.extra_data_2[0], half_width, -JOIN_OVERLAP))
+                    .max(segment_end_signed_dist(from_end, [end_angle.cos(), end_angle.sin()], primitive.extra_data_2[1], half_width, 2.0 * JOIN_OVERLAP));
+                stroke_and_fill(dist, smoothness, 0.0, inner_colour, outer_colour)
+            },
+            9 => {
+                let vertices = path_triangle_vertices(primitive).unwrap();
+                let dist = outline_signed_dist(pixel_position(texture_coord), vertices, primitive.extra_data_2[2]);
+                stroke_and_fill(dist, smoothness, 0.0, inner_colour, outer_colour)
+            },
+            _ => ([0.0, 0.0, 0.0], 0.0)
+        }
+    }
+
+    fn glyph_dist(&self, glyph_index: u16, texture_coord: [f32; 2]) -> f32 {
+        let glyph = &self.glyphs[(glyph_index as usize).min(self.glyphs.len() - 1)];
+        let sample = sample_bilinear(glyph, texture_coord);
+        median(sample[0], sample[1], sample[2])
+    }
+}
+
+// A path triangle's vertices, as pixel offsets from its path's centre
+fn path_triangle_vertices(primitive: &RenderPrimitive) -> Option<[[f32; 2]; 3]> {
+    if primitive.identification[0] != 9 {
+        return None;
+    }
+    let [a_x, a_y, b_x, b_y] = primitive.extra_data_1;
+    let [width, height] = primitive.dimensions;
+    Some([[a_x * width, a_y * height], [b_x * width, b_y * height], [primitive.extra_data_2[0] * width, primitive.extra_data_2[1] * height]])
+}
+
+// Pixels on an edge two triangles share go to exactly one of them, as on the GPU. Each edge is
+// taken the same way round whichever triangle it belongs to, so the triangles either side of it
+// see each other's inside on opposite sides.
+fn covers_triangle(point: [f32; 2], vertices: [[f32; 2]; 3]) -> bool {
+    (0..3).all(|index| {
+        let (from, to) = (vertices[index], vertices[(index + 1) % 3]);
+        let (from, to) = if (from[0], from[1]) < (to[0], to[1]) { (from, to) } else { (to, from) };
+        let inside = cross(subtract(to, from), subtract(vertices[(index + 2) % 3], from));
+        let side = cross(subtract(to, from), subtract(point, from));
+        if side == 0.0 { inside > 0.0 } else { side * inside > 0.0 }
+    })
+}
+
+// A function's value and its GLSL `fwidth`, the change towards the neighbouring pixels
+fn fwidth<F: Fn([f32; 2]) -> f32>(function: F, texture_coord: [f32; 2], texture_steps: [[f32; 2]; 2]) -> (f32, f32) {
+    let value = function(texture_coord);
+    let next_x = function([texture_coord[0] + texture_steps[0][0], texture_coord[1] + texture_steps[0][1]]);
+    let next_y = function([texture_coord[0] + texture_steps[1][0], texture_coord[1] + texture_steps[1][1]]);
+    (value, (next_x - value).abs() + (next_y - value).abs())
+}
+
+fn pixel_range(start: f32, end: f32, limit: u32) -> std::ops::Range<u32> {
+    let first = (start - 0.5).ceil().max(0.0) as u32;
+    let last = ((end - 0.5).ceil().max(0.0) as u32).min(limit);
+    first..last.max(first)
+}
+
+// Linear filtering with the mirrored wrapping glium samplers default to
+fn sample_bilinear(image: &image::RgbaImage, texture_coord: [f32; 2]) -> [f32; 3] {
+    let (width, height) = image.dimensions();
+    let x = texture_coord[0] * width as f32 - 0.5;
+    let y = texture_coord[1] * height as f32 - 0.5;
+    let x0 = x.floor();
+    let y0 = y.floor();
+    let x_weight = x - x0;
+    let y_weight = y - y0;
+
+    let texel = |x: f32, y: f32| {
+        let pixel = image.get_pixel(mirror(x as i64, width), mirror(y as i64, height));
+        [pixel[0] as f32 / 255.0, pixel[1] as f32 / 255.0, pixel[2] as f32 / 255.0]
+    };
+
+    let top = mix(texel(x0, y0), texel(x0 + 1.0, y0), x_weight);
+    let bottom = mix(texel(x0, y0 + 1.0), texel(x0 + 1.0, y0 + 1.0), x_weight);
+    mix(top, bottom, y_weight)
+}
+
+fn mirror(index: i64, size: u32) -> u32 {
+    let size = size as i64;
+    let wrapped = index.rem_euclid(size * 2);
+    (if wrapped >= size { size * 2 - 1 - wrapped } else { wrapped }) as u32
+}
+
+fn to_u8(value: f32) -> u8 {
+    (value.clamp(0.0, 1.0) * 255.0).round() as u8
+}
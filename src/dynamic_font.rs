@@ -0,0 +1,123 @@
+use glium::backend::Facade;
+use glium::texture::pixel_buffer::PixelBuffer;
+use glium::texture::texture2d_array::Texture2dArray;
+use glium::texture::{MipmapsOption, UncompressedFloatFormat};
+use crate::font::{Font, GlyphMetrics};
+use crate::glyph_cache::{CacheStats, GlyphCache};
+use crate::msdf::MsdfGenerator;
+
+/// A font texture array filled in from an outline font as new characters are needed,
+/// evicting the least recently used glyphs once it is full.
+///
+/// Each frame, call `begin_frame`, then `prepare` with all the text to draw, then lay it
+/// out with `font()` and draw it with `SdfRenderer::draw_with_font_buffer(.., font_buffer())`.
+/// Primitives laid out in earlier frames may point at layers that have since been reused.
+pub struct DynamicFontAtlas {
+    generator: MsdfGenerator,
+    font: Font,
+    font_buffer: Texture2dArray,
+    upload_buffer: PixelBuffer<(u8, u8, u8, u8)>,
+    cache: GlyphCache
+}
+
+impl DynamicFontAtlas {
+    pub fn new<F: Facade + ?Sized>(facade: &F, generator: MsdfGenerator, layer_capacity: u16) -> Self {
+        let (width, height) = generator.layer_dimensions();
+        let font_buffer = Texture2dArray::empty_with_format(
+            facade,
+            UncompressedFloatFormat::U8U8U8U8,
+            MipmapsOption::NoMipmap,
+            width,
+            height,
+            layer_capacity as u32
+        ).unwrap();
+
+        Self {
+            font: generator.empty_font(),
+            generator,
+            font_buffer,
+            upload_buffer: PixelBuffer::new_empty(facade, (width * height) as usize),
+            cache: GlyphCache::new(layer_capacity)
+        }
+    }
+
+    pub fn begin_frame(&mut self) {
+        self.cache.begin_frame();
+    }
+
+    /// Makes sure the glyphs of `text` are in the atlas, generating and uploading the missing
+    /// ones. Characters the font lacks are left out, and so are new glyphs when every layer
+    /// is already in use this frame, counted once a frame in `stats().overflows`.
+    pub fn prepare(&mut self, text: &str) {
+        for character in text.chars() {
+            if character.is_control() || self.cache.overflowed(character) {
+                continue;
+            }
+            // Whitespace stays in the font without taking a layer
+            if let Some(GlyphMetrics { glyph_index: None, .. }) = self.font.glyph(character) {
+                continue;
+            }
+            if self.cache.get(character).is_some() {
+                continue;
+            }
+            // Generating is the slow part, so glyphs with no layer to go to are skipped before it
+            if self.cache.is_full() && !character.is_whitespace() {
+                self.cache.overflow(character);
+                continue;
+            }
+
+            let glyph = match self.generator.generate(character) {
+                Some(glyph) => glyph,
+                None => continue
+            };
+
+            let glyph_index = match glyph.image {
+                Some(image) => {
+                    let (layer, evicted) = match self.cache.insert(character) {
+                        Some(assigned) => assigned,
+                        None => continue
+                    };
+                    if let Some(evicted) = evicted {
+                        self.font.remove_glyph(evicted);
+                    }
+                    self.upload(&image, layer as u32);
+                    Some(layer)
+                },
+                None => None
+            };
+
+            self.font.insert_glyph(character, GlyphMetrics {
+                glyph_index,
+                advance: glyph.advance,
+                plane_bounds: glyph.plane_bounds
+            });
+        }
+    }
+
+    fn upload(&self, image: &image::RgbaImage, layer: u32) {
+        let pixels: Vec<(u8, u8, u8, u8)> = image.pixels().map(|pixel| (pixel[0], pixel[1], pixel[2], pixel[3])).collect();
+        self.upload_buffer.write(&pixels);
+        self.font_buffer.main_level().raw_upload_from_pixel_buffer(
+            self.upload_buffer.as_slice(),
+            0..image.width(),
+            0..image.height(),
+            layer..layer + 1
+        );
+    }
+
+    pub fn font(&self) -> &Font {
+        &self.font
+    }
+
+    pub fn font_buffer(&self) -> &Texture2dArray {
+        &self.font_buffer
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.cache.reset_stats();
+    }
+}
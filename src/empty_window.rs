@@ -1,3 +1,4 @@
+#[allow(clippy::needless_return, clippy::single_match)]
 pub fn run() {
     use glium::{glutin, Surface};
 
@@ -0,0 +1,354 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::io::Cursor;
+use std::path::Path;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug)]
+pub enum FontError {
+    Io(std::io::Error),
+    Metadata(serde_json::Error),
+    Image(image::ImageError),
+    Face(ttf_parser::FaceParsingError),
+    PixelsPerEm(f32),
+    AtlasSize(f32),
+    EmptyGlyphCell(u32)
+}
+
+impl fmt::Display for FontError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FontError::Io(error) => write!(f, "could not read font atlas: {}", error),
+            FontError::Metadata(error) => write!(f, "could not parse font atlas metadata: {}", error),
+            FontError::Image(error) => write!(f, "could not load font atlas image: {}", error),
+            FontError::Face(error) => write!(f, "could not parse font: {}", error),
+            FontError::PixelsPerEm(pixels_per_em) => write!(f, "pixels per em must be positive and finite, not {}", pixels_per_em),
+            FontError::AtlasSize(size) => write!(f, "font atlas size must be positive and finite, not {}", size),
+            FontError::EmptyGlyphCell(unicode) => write!(f, "font atlas cell for U+{:04X} has no area", unicode)
+        }
+    }
+}
+
+impl std::error::Error for FontError {}
+
+impl From<std::io::Error> for FontError {
+    fn from(error: std::io::Error) -> Self {
+        FontError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for FontError {
+    fn from(error: serde_json::Error) -> Self {
+        FontError::Metadata(error)
+    }
+}
+
+impl From<image::ImageError> for FontError {
+    fn from(error: image::ImageError) -> Self {
+        FontError::Image(error)
+    }
+}
+
+// The msdf-atlas-gen JSON layout
+#[derive(Serialize, Deserialize)]
+pub(crate) struct AtlasMetadata {
+    pub(crate) atlas: AtlasDescription,
+    pub(crate) metrics: FontMetrics,
+    pub(crate) glyphs: Vec<GlyphDescription>
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AtlasDescription {
+    #[serde(rename = "type", default)]
+    pub(crate) kind: String,
+    #[serde(default)]
+    pub(crate) distance_range: f32,
+    pub(crate) size: f32,
+    #[serde(default)]
+    pub(crate) width: u32,
+    pub(crate) height: u32,
+    #[serde(default)]
+    pub(crate) y_origin: YOrigin
+}
+
+#[derive(Serialize, Deserialize, PartialEq, Default)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum YOrigin {
+    #[default]
+    Bottom,
+    Top
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct FontMetrics {
+    #[serde(default)]
+    pub(crate) em_size: f32,
+    pub(crate) line_height: f32,
+    pub(crate) ascender: f32,
+    pub(crate) descender: f32
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct GlyphDescription {
+    pub(crate) unicode: u32,
+    pub(crate) advance: f32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) plane_bounds: Option<GlyphBounds>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) atlas_bounds: Option<GlyphBounds>
+}
+
+#[derive(Serialize, Deserialize, Copy, Clone, Debug, PartialEq)]
+pub struct GlyphBounds {
+    pub left: f32,
+    pub bottom: f32,
+    pub right: f32,
+    pub top: f32
+}
+
+/// Metrics of one glyph in ems, with plane bounds relative to the baseline and y pointing up.
+/// Glyphs without an outline, such as spaces, have no texture array layer.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GlyphMetrics {
+    pub glyph_index: Option<u16>,
+    pub advance: f32,
+    pub plane_bounds: Option<GlyphBounds>
+}
+
+/// Maps characters to layers of the font texture array. All metrics are in ems.
+#[derive(Clone, Debug)]
+pub struct Font {
+    glyphs: HashMap<char, GlyphMetrics>,
+    pub layer_size: [f32; 2],
+    pub ascent: f32,
+    pub descent: f32,
+    pub line_height: f32
+}
+
+impl Font {
+    pub fn new(layer_size: [f32; 2], ascent: f32, descent: f32, line_height: f32) -> Self {
+        Self {
+            glyphs: HashMap::new(),
+            layer_size,
+            ascent,
+            descent,
+            line_height
+        }
+    }
+
+    pub fn insert_glyph(&mut self, character: char, metrics: GlyphMetrics) {
+        self.glyphs.insert(character, metrics);
+    }
+
+    pub fn remove_glyph(&mut self, character: char) -> Option<GlyphMetrics> {
+        self.glyphs.remove(&character)
+    }
+
+    pub fn glyph(&self, character: char) -> Option<&GlyphMetrics> {
+        self.glyphs.get(&character)
+    }
+}
+
+/// A font and the texture array layers its glyphs index, each glyph copied into
+/// the top left of its own layer.
+pub struct FontAtlas {
+    pub font: Font,
+    pub layers: Vec<image::RgbaImage>
+}
+
+impl FontAtlas {
+    /// The project's Segoe UI strip, Basic Latin with metrics measured by `GlyphStrip::metadata`.
+    pub fn segoe_ui() -> Self {
+        let atlas_image = image::load(Cursor::new(&include_bytes!("../images/segoeui-1.png")[..]), image::ImageFormat::Png).unwrap();
+        FontAtlas::load(include_str!("../images/segoeui-1.json"), &atlas_image.to_rgba8()).unwrap()
+    }
+
+    /// DejaVu Sans covering Basic Latin and Latin-1, generated by `MsdfGenerator::save_atlas`.
+    pub fn dejavu_sans() -> Self {
+        let atlas_image = image::load(Cursor::new(&include_bytes!("../images/dejavu-sans.png")[..]), image::ImageFormat::Png).unwrap();
+        FontAtlas::load(include_str!("../images/dejavu-sans.json"), &atlas_image.to_rgba8()).unwrap()
+    }
+
+    pub fn open<P: AsRef<Path>, Q: AsRef<Path>>(metadata_path: P, atlas_image_path: Q) -> Result<Self, FontError> {
+        let metadata = std::fs::read_to_string(metadata_path)?;
+        let atlas_image = image::open(atlas_image_path)?.to_rgba8();
+        FontAtlas::load(&metadata, &atlas_image)
+    }
+
+    /// Loads an atlas from msdf-atlas-gen JSON metadata, rejecting sizes that are not positive
+    /// and finite and glyph cells with no area, either of which would scale glyphs by infinity.
+    pub fn load(metadata: &str, atlas_image: &image::RgbaImage) -> Result<Self, FontError> {
+        let metadata: AtlasMetadata = serde_json::from_str(metadata)?;
+        if !(metadata.atlas.size.is_finite() && metadata.atlas.size > 0.0) {
+            return Err(FontError::AtlasSize(metadata.atlas.size));
+        }
+
+        let atlas_rects = metadata.glyphs
+            .iter()
+            .map(|glyph| match glyph.atlas_bounds.map(|bounds| atlas_rect(&bounds, &metadata.atlas)) {
+                Some((_, _, 0, _)) | Some((_, _, _, 0)) => Err(FontError::EmptyGlyphCell(glyph.unicode)),
+                atlas_rect => Ok(atlas_rect)
+            })
+            .collect::<Result<Vec<Option<(u32, u32, u32, u32)>>, FontError>>()?;
+
+        let layer_dimensions = atlas_rects
+            .iter()
+            .flatten()
+            .fold((1, 1), |(width, height), rect| (width.max(rect.2), height.max(rect.3)));
+
+        let mut layers = vec!();
+        let mut glyphs = HashMap::new();
+        for (glyph, atlas_rect) in metadata.glyphs.iter().zip(atlas_rects) {
+            let character = match std::char::from_u32(glyph.unicode) {
+                Some(character) => character,
+                None => continue
+            };
+
+            let glyph_index = atlas_rect.map(|(x, y, width, height)| {
+                let mut layer = image::RgbaImage::from_pixel(layer_dimensions.0, layer_dimensions.1, image::Rgba([0, 0, 0, 255]));
+                let glyph_image = image::imageops::crop_imm(atlas_image, x, y, width, height).to_image();
+                image::imageops::replace(&mut layer, &glyph_image, 0, 0);
+                layers.push(layer);
+                (layers.len() - 1) as u16
+            });
+
+            glyphs.insert(character, GlyphMetrics {
+                glyph_index,
+                advance: glyph.advance,
+                plane_bounds: glyph.plane_bounds
+            });
+        }
+
+        let font = Font {
+            glyphs,
+            layer_size: [
+                layer_dimensions.0 as f32 / metadata.atlas.size,
+                layer_dimensions.1 as f32 / metadata.atlas.size
+            ],
+            ascent: metadata.metrics.ascender,
+            descent: metadata.metrics.descender,
+            line_height: metadata.metrics.line_height
+        };
+
+        Ok(Self { font, layers })
+    }
+}
+
+/// An atlas of square cells stacked top to bottom, one glyph each, drawn with the pen origin at
+/// the same point in every cell. Such strips record no metrics, so `metadata` measures them.
+pub struct GlyphStrip {
+    pub cell_size: u32,
+    pub pixels_per_em: f32,
+    /// The pen origin in pixels from the top left of each cell, on the baseline.
+    pub origin: [f32; 2],
+    /// Each character with the cell it is drawn in.
+    pub cells: Vec<(char, u32)>,
+    pub ascender: f32,
+    pub descender: f32,
+    pub line_height: f32,
+    /// The advance in ems of cells without an outline to measure, such as spaces.
+    pub blank_advance: f32
+}
+
+impl GlyphStrip {
+    /// Metadata for `strip` in the msdf-atlas-gen JSON layout that `FontAtlas::load` reads. Every
+    /// glyph's plane bounds are its whole cell, and its advance spans its outline with the same
+    /// side bearing to the right as the outline leaves to the left of the origin.
+    pub fn metadata(&self, strip: &image::RgbaImage) -> String {
+        let cell_size = self.cell_size as f32;
+        let plane_bounds = GlyphBounds {
+            left: -self.origin[0] / self.pixels_per_em,
+            bottom: (self.origin[1] - cell_size) / self.pixels_per_em,
+            right: (cell_size - self.origin[0]) / self.pixels_per_em,
+            top: self.origin[1] / self.pixels_per_em
+        };
+
+        let glyphs = self.cells.iter().map(|&(character, cell)| {
+            match self.outline_columns(strip, cell) {
+                Some((left, right)) => GlyphDescription {
+                    unicode: character as u32,
+                    advance: (right - self.origin[0] + left - self.origin[0]) / self.pixels_per_em,
+                    plane_bounds: Some(plane_bounds),
+                    atlas_bounds: Some(GlyphBounds {
+                        left: 0.0,
+                        bottom: ((cell + 1) * self.cell_size) as f32,
+                        right: cell_size,
+                        top: (cell * self.cell_size) as f32
+                    })
+                },
+                None => GlyphDescription { unicode: character as u32, advance: self.blank_advance, plane_bounds: None, atlas_bounds: None }
+            }
+        }).collect();
+
+        let metadata = AtlasMetadata {
+            atlas: AtlasDescription {
+                kind: "msdf".to_string(),
+                distance_range: strip_distance_range(strip),
+                size: self.pixels_per_em,
+                width: strip.width(),
+                height: strip.height(),
+                y_origin: YOrigin::Top
+            },
+            metrics: FontMetrics {
+                em_size: 1.0,
+                line_height: self.line_height,
+                ascender: self.ascender,
+                descender: self.descender
+            },
+            glyphs
+        };
+        serde_json::to_string_pretty(&metadata).unwrap()
+    }
+
+    // The left and right edges of the columns a cell's outline covers, None for blank cells
+    fn outline_columns(&self, strip: &image::RgbaImage, cell: u32) -> Option<(f32, f32)> {
+        let top = cell * self.cell_size;
+        if top + self.cell_size > strip.height() {
+            return None;
+        }
+        let covered = |x: u32| (top..top + self.cell_size).any(|y| strip_median(strip.get_pixel(x, y).0) > 0.5);
+        let left = (0..self.cell_size.min(strip.width())).find(|&x| covered(x))?;
+        let right = (0..self.cell_size.min(strip.width())).rev().find(|&x| covered(x))?;
+        Some((left as f32, (right + 1) as f32))
+    }
+}
+
+fn strip_median(pixel: [u8; 4]) -> f32 {
+    let [r, g, b, _] = pixel.map(|channel| channel as f32 / 255.0);
+    r.max(g).min(r.min(g).max(b))
+}
+
+// Distance fields change by one over the range, so the range is the reciprocal of the average
+// step between neighbouring pixels either side of an outline, across edges running straight down
+fn strip_distance_range(strip: &image::RgbaImage) -> f32 {
+    let median = |x: u32, y: u32| strip_median(strip.get_pixel(x, y).0);
+    let (mut total_step, mut steps) = (0.0, 0);
+    for y in 1..strip.height().saturating_sub(1) {
+        for x in 1..strip.width() {
+            let (from, to) = (median(x - 1, y), median(x, y));
+            let unclamped = |value: f32| value > 0.0 && value < 1.0;
+            let vertical = (median(x, y - 1) - median(x, y + 1)).abs() < 1.0 / 255.0;
+            if (from > 0.5) != (to > 0.5) && unclamped(from) && unclamped(to) && vertical {
+                total_step += (to - from).abs();
+                steps += 1;
+            }
+        }
+    }
+    if steps == 0 { 0.0 } else { steps as f32 / total_step }
+}
+
+// Atlas bounds as a top down pixel rectangle
+fn atlas_rect(bounds: &GlyphBounds, atlas: &AtlasDescription) -> (u32, u32, u32, u32) {
+    let (top, bottom) = match atlas.y_origin {
+        YOrigin::Top => (bounds.top, bounds.bottom),
+        YOrigin::Bottom => (atlas.height as f32 - bounds.top, atlas.height as f32 - bounds.bottom)
+    };
+    let x = bounds.left.min(bounds.right).round() as u32;
+    let y = top.min(bottom).round() as u32;
+    let width = (bounds.right - bounds.left).abs().round() as u32;
+    let height = (bottom - top).abs().round() as u32;
+    (x, y, width, height)
+}
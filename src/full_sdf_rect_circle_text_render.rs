@@ -1,4 +1,14 @@
-use std::io::Cursor;
+use rust_glium::font::FontAtlas;
+use rust_glium::gradient::Gradient;
+use rust_glium::path::{FillRule, Path};
+use rust_glium::primitive_stream::PrimitiveStream;
+use rust_glium::primitives::{LineCap, RenderPrimitive};
+use rust_glium::sdf_renderer::SdfRenderer;
+use rust_glium::stroke::{stroke_cubic, stroke_polyline, LineJoin, StrokeStyle};
+use rust_glium::svg::SvgIcon;
+use rust_glium::text::layout_text;
+use rust_glium::transform::Transform;
+use rust_glium::window::WindowMetrics;
 
 pub fn run() {
     #[allow(unused_imports)]
@@ -9,353 +19,75 @@ pub fn run() {
     let cb = glutin::ContextBuilder::new();
     let display = glium::Display::new(wb, cb, &event_loop).unwrap();
 
-    #[derive(Copy, Clone)]
-    pub struct RenderPrimitive {
-        position: [u16; 2],
-        dimensions: [u16; 2],
-        inner_colour: [f32; 4],
-        outer_colour: [f32; 4],
-        identification: [u16; 2],
-        extra_data_1: [f32; 4],
-        extra_data_2: [f32; 4]
-    }
-
-    impl RenderPrimitive {
-        pub fn with_position_size_colours_identification_and_data(
-            position: [u16; 2],
-            dimensions: [u16; 2],
-            inner_colour: [f32; 4],
-            outer_colour: [f32; 4],
-            identification: [u16; 2],
-            extra_data_1: [f32; 4],
-            extra_data_2: [f32; 4]) -> Self {
-            Self {
-                position,
-                dimensions,
-                inner_colour,
-                outer_colour,
-                identification,
-                extra_data_1,
-                extra_data_2
-            }
-        }
-        
-        pub fn circle(
-            position: [u16; 2],
-            radius: u16,
-            inner_colour: [f32; 4],
-            outer_colour: [f32; 4],
-            stroke_width: f32) -> Self {
-            RenderPrimitive::with_position_size_colours_identification_and_data(
-                position,
-                [radius, radius],
-                inner_colour,
-                outer_colour,
-                [0, 0],
-                [stroke_width, 0.0, 0.0, 0.0],
-                [0.0, 0.0, 0.0, 0.0]
-            ) 
-        }
-
-        pub fn rectangle(
-            position: [u16; 2],
-            dimensions: [u16; 2],
-            inner_colour: [f32; 4],
-            outer_colour: [f32; 4],
-            stroke_width: f32,
-            corner_radii: [f32; 4]) -> Self {
-            RenderPrimitive::with_position_size_colours_identification_and_data(
-                position,
-                dimensions,
-                inner_colour,
-                outer_colour,
-                [1, 0],
-                [stroke_width, 0.0, 0.0, 0.0],
-                corner_radii
-            ) 
-        }
-
-        pub fn text(
-            position: [u16; 2],
-            dimensions: [u16; 2],
-            colour: [f32; 4],
-            glyph_index: u16) -> Self {
-            RenderPrimitive::with_position_size_colours_identification_and_data(
-                position,
-                dimensions,
-                colour,
-                colour,
-                [2, glyph_index],
-                [0.0, 0.0, 0.0, 0.0],
-                [0.0, 0.0, 0.0, 0.0]
-            ) 
-        }
- 
-        pub fn expand_dimensions(&mut self, increase_in_pixels: i16) {
-            let unsigned_movement_in_pixels = increase_in_pixels.wrapping_abs() as u16;
-            if increase_in_pixels > 0 {
-                self.dimensions[0] = &self.dimensions[0] + unsigned_movement_in_pixels;
-                self.dimensions[1] = &self.dimensions[1] + unsigned_movement_in_pixels;
-            } else {
-                
-                self.dimensions[0] = &self.dimensions[0] - unsigned_movement_in_pixels;
-                self.dimensions[1] = &self.dimensions[1] - unsigned_movement_in_pixels;
-            }
-        }
-    }
-
-    implement_vertex!(
-        RenderPrimitive,
-        position, 
-        dimensions, 
-        inner_colour,
-        outer_colour,
-        identification,
-        extra_data_1,
-        extra_data_2
-    );
-
     let white = [1.0, 1.0, 1.0, 1.0];        
     let black = [0.0, 0.0, 0.0, 1.0];        
     
-    let mut vertices = glium::vertex::VertexBuffer::dynamic(
-        &display, 
-        &[
-            RenderPrimitive::circle([100, 100], 100, white, black, 5.0),
-            RenderPrimitive::rectangle([400, 400], [300, 300], white, black, 20.0, [0.1, 0.3, 0.4, 0.2]),
-            RenderPrimitive::text([400, 100], [600, 600], black, 37),
-        ]).unwrap();
-
-    let indices = glium::index::NoIndices(glium::index::PrimitiveType::Points);
-
-    let vertex_shader_src = r#"
-        #version 330 core
-
-        uniform vec2 uResolution;
-        
-        layout (location = 0) in ivec2 position;
-        layout (location = 1) in ivec2 dimensions;
-        layout (location = 2) in vec4 inner_colour;
-        layout (location = 3) in vec4 outer_colour;
-        layout (location = 4) in ivec2 identification;
-        layout (location = 5) in vec4 extra_data_1;
-        layout (location = 6) in vec4 extra_data_2;
-        
-        out VS_OUT
-        {
-            vec2 dimensions;
-            vec4 inner_colour;
-            vec4 outer_colour;
-            flat ivec2 identification;
-            vec4 extra_data_1;
-            vec4 extra_data_2;
-        } vs_out;
-        
-        vec2 toClipSpace(vec2 resolution, vec2 from)
-        {
-            return vec2(
-                from.x / (resolution.x / 2.0) - 1.0,
-                1.0 - (from.y / (resolution.y / 2.0))
-            );
-        }
-        
-        void main()
-        {
-            gl_Position = vec4(toClipSpace(uResolution, vec2(position)), 0.0, 1.0);
-            vs_out.dimensions = dimensions;
-            vs_out.inner_colour = inner_colour;
-            vs_out.outer_colour = outer_colour;
-            vs_out.identification = identification;
-            vs_out.extra_data_1 = extra_data_1;
-            vs_out.extra_data_2 = extra_data_2;
-        }
-    "#;
-
-    let geometry_shader_src = r#"
-        #version 330 core
-
-        layout(points) in;
-        layout(triangle_strip, max_vertices = 4) out;
-
-        uniform vec2 uResolution;
-
-        in VS_OUT
-        {
-            vec2 dimensions;
-            vec4 inner_colour;
-            vec4 outer_colour;
-            flat ivec2 identification;
-            vec4 extra_data_1;
-            vec4 extra_data_2;
-        } gm_in[];
-
-        out GM_OUT 
-        {
-            vec2 dimensions;
-            vec2 texture_coord;
-            vec4 inner_colour;
-            vec4 outer_colour;
-            flat ivec2 identification;
-            vec4 extra_data_1;
-            vec4 extra_data_2;
-        } gm_out;
-
-        void createVertex(vec2 pos, vec2 scale, vec2 corner, float u, float v) {
-            vec2 scaled = scale * corner;
-            vec2 transformed = pos + scaled;
-            gl_Position = vec4(transformed, 0.0, 1.0);
-            gm_out.texture_coord = vec2(u, v);
-            gm_out.dimensions = gm_in[0].dimensions;
-            gm_out.inner_colour = gm_in[0].inner_colour;
-            gm_out.outer_colour = gm_in[0].outer_colour;
-            gm_out.identification = gm_in[0].identification;
-            gm_out.extra_data_1 = gm_in[0].extra_data_1;
-            gm_out.extra_data_2 = gm_in[0].extra_data_2;
-            EmitVertex();
-        }
-
-        void main()
-        {
-            vec2 pos = gl_in[0].gl_Position.xy;;
-            vec2 size = gm_in[0].dimensions / uResolution.xy; 
-
-            mat3 scale = mat3(
-                size.x, 0.0, 0.0,
-                0.0, size.y, 0.0,
-                0.0, 0.0, 1.0
-            );
+    let font_atlas = FontAtlas::segoe_ui();
+    let glyph_index = font_atlas.font.glyph('C').and_then(|glyph| glyph.glyph_index).unwrap();
 
-            float one = 1.0;
-            vec2 bottomLeft = vec2(-one, -one);
-            vec2 bottomRight = vec2(one, -one);
-            vec2 topLeft = vec2(-one, one);
-            vec2 topRight = vec2(one, one);
-            
-            createVertex(pos, size, bottomLeft, 0.0, 1.0);
-            createVertex(pos, size, bottomRight, 1.0, 1.0);
-            createVertex(pos, size, topLeft, 0.0, 0.0);
-            createVertex(pos, size, topRight, 1.0, 0.0);
-
-            EndPrimitive();
-        }
-    "#;
-
-    let fragment_shader_src = r#"
-        #version 330 core
-
-        uniform sampler2DArray font_buffer;
-        float smoothness = 0.002;
-        
-        in GM_OUT 
-        {
-            vec2 dimensions;
-            vec2 texture_coord;
-            vec4 inner_colour;
-            vec4 outer_colour;
-            flat ivec2 identification;
-            vec4 extra_data_1;
-            vec4 extra_data_2;
-        } fs_in;
-        
-        out vec4 Color;
-        
-        float median(float r, float g, float b)
-        {
-            return max(min(r, g), min(max(r, g), b));
-        }
-        
-        float circle_signed_dist(vec2 position, float radius) 
-        {
-            return length(position) - radius;
-        }
-        
-        float box_signed_dist(in vec2 position, in vec4 corner_radii)
-        {
-            vec2 bounds = vec2(0.5);
-            vec2 quadrant_position = step(vec2(0.5), position);
-            int corner_radius_index = int(quadrant_position.x) + int(quadrant_position.y) * 2;
-            float corner_radius = corner_radii[corner_radius_index];
-        
-            vec2 centred_position = position - 0.5;    
-            vec2 offset = abs(centred_position) - bounds + corner_radius;
-            return min(max(offset.x, offset.y), 0.0) + length(max(offset, 0.0)) - corner_radius;
-        }
-        
-        void main()
-        {
-            vec3 inner_colour = fs_in.inner_colour.rgb;
-            vec3 outer_colour = fs_in.outer_colour.rgb;
-            float stroke_width = fs_in.extra_data_1.r / fs_in.dimensions.x;
-        
-            float alpha = 0.00;
-            vec3 current_colour;
-            
-            if(fs_in.identification.r == 0) 
-            {
-                float outer_radius = 0.5;
-                float dist = circle_signed_dist(fs_in.texture_coord - 0.5, outer_radius);
-                float outer = smoothstep(smoothness, -smoothness, dist);
-                float inner = smoothstep(-stroke_width + smoothness, -stroke_width - smoothness, dist);
-                alpha = smoothstep(0.00, -smoothness, dist);
-                current_colour = mix(outer_colour, inner_colour, inner) * outer;
-            }
-            
-            if(fs_in.identification.r == 1) 
-            {
-                vec4 corner_radii = fs_in.extra_data_2;
-                float dist = box_signed_dist(fs_in.texture_coord, corner_radii);
-                float outer = smoothstep(smoothness, -smoothness, dist);
-                float inner = smoothstep(-stroke_width + smoothness, -stroke_width - smoothness, dist);
-                alpha = smoothstep(0.00, -smoothness, dist);
-                current_colour = mix(outer_colour, inner_colour, inner) * outer;
-            }
-        
-            if(fs_in.identification.r == 2) 
-            {
-                vec3 sample = texture(font_buffer, vec3(fs_in.texture_coord, fs_in.identification.g)).rgb;
-                float dist = median(sample.r, sample.g, sample.b);
-                float width = fwidth(dist);
-                alpha = smoothstep(0.5 - width, 0.5 + width, dist);
-                current_colour = outer_colour;
-            }
-        
-            Color = vec4(current_colour, alpha);
-        }
-    "#;
-
-    let program = glium::Program::from_source(&display, vertex_shader_src, fragment_shader_src, Some(geometry_shader_src)).unwrap();
-    
-    let framebuffer_dimensions = display.get_framebuffer_dimensions();
-    let resolution: [f32;2] = [framebuffer_dimensions.0 as f32, framebuffer_dimensions.1 as f32];
-    
-    println!("resolution: {:?}", resolution);
-    
-    let mut glyphs = vec!();
-    let glyph_dimensions = (96, 96);    
-    
-    println!("loading font texture");
-    
-    let mut font_image = image::load(Cursor::new(&include_bytes!("../images/segoeui-1.png")[..]), image::ImageFormat::Png)
-        .unwrap()
-        .to_rgba8();
-    
-    println!("loaded font texture");
-    
-    let font_image_dimensions = font_image.dimensions(); 
-    let glyph_count = font_image_dimensions.1 / glyph_dimensions.1;
-    
-    println!("making raw glyph texture array of count {}", glyph_count);
-    
-    for glyph_index in 0..glyph_count {
-        let glyph_image = image::imageops::crop(&mut font_image, 0, glyph_index * glyph_dimensions.1, glyph_dimensions.0, glyph_dimensions.1).to_image();
-        let glyph_image_dimensions = glyph_image.dimensions();
-        glyphs.push(glium::texture::RawImage2d::from_raw_rgba_reversed(&glyph_image.into_raw(), glyph_image_dimensions));
+    let mut primitives = vec!(
+        RenderPrimitive::circle([100.0, 100.0], 100.0, white, black, 5.0),
+        RenderPrimitive::rectangle([400.0, 400.0], [300.0, 300.0], white, black, 20.0, [0.1, 0.3, 0.4, 0.2]),
+        RenderPrimitive::text([400.0, 100.0], [600.0, 600.0], black, glyph_index),
+    );
+    primitives.extend(layout_text("Signed distance fields", &font_atlas.font, 48.0, [100.0, 600.0], white));
+
+    let red = [0.9, 0.2, 0.2, 1.0];
+    let quarter_turn = std::f32::consts::FRAC_PI_2;
+    primitives.extend(vec!(
+        RenderPrimitive::arc([850.0, 150.0], 200.0, 1.5 * quarter_turn, 4.5 * quarter_turn, 20.0, white, black, 2.0),
+        RenderPrimitive::arc([850.0, 150.0], 200.0, 1.5 * quarter_turn, 3.5 * quarter_turn, 20.0, red, black, 2.0),
+        RenderPrimitive::line([850.0, 150.0], [900.0, 100.0], 6.0, black, LineCap::Round),
+        RenderPrimitive::pie([850.0, 400.0], 160.0, 0.0, 2.6 * quarter_turn, red, black, 3.0),
+        RenderPrimitive::ellipse([850.0, 600.0], [200.0, 90.0], white, black, 4.0),
+        RenderPrimitive::triangle([[1050.0, 80.0], [1130.0, 220.0], [970.0, 220.0]], white, black, 4.0),
+        RenderPrimitive::polygon([1050.0, 400.0], 160.0, 6, white, black, 4.0),
+    ));
+
+    // A line chart, and a wire between two nodes
+    let chart = [[60.0, 760.0], [140.0, 700.0], [220.0, 730.0], [300.0, 660.0], [380.0, 690.0], [460.0, 640.0]];
+    let chart_style = StrokeStyle { join: LineJoin::Round, cap: LineCap::Round, ..StrokeStyle::new(5.0, red) };
+    primitives.extend(stroke_polyline(&chart, false, &chart_style));
+    primitives.extend(stroke_cubic([560.0, 660.0], [680.0, 660.0], [600.0, 760.0], [720.0, 760.0], &StrokeStyle::new(4.0, white)));
+
+    // A filled star over the rectangle's corner, its middle left open by the even-odd rule
+    let mut star = Path::new();
+    for point in 0..5 {
+        let angle = -quarter_turn + point as f32 * 0.8 * std::f32::consts::PI;
+        star.line_to([560.0 + 90.0 * angle.cos(), 260.0 + 90.0 * angle.sin()]);
     }
+    star.close();
+    primitives.extend(star.fill(FillRule::EvenOdd, [0.95, 0.75, 0.2, 0.9]));
+    primitives.extend(star.stroke(&StrokeStyle::new(3.0, black)));
+
+    // Icons in the style of an icon set, one tinted through currentColor
+    let heart = SvgIcon::load(r#"<svg viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2" stroke-linecap="round" stroke-linejoin="round">
+        <path d="M20.84 4.61a5.5 5.5 0 0 0-7.78 0L12 5.67l-1.06-1.06a5.5 5.5 0 0 0-7.78 7.78l1.06 1.06L12 21.23l7.78-7.78 1.06-1.06a5.5 5.5 0 0 0 0-7.78z"/>
+    </svg>"#).unwrap();
+    let badge = SvgIcon::load(r##"<svg viewBox="0 0 24 24">
+        <rect x="2" y="2" width="20" height="20" rx="5" fill="#3a7" stroke="#124" stroke-width="1.5"/>
+        <path d="M7 12l3 3 7-7" fill="none" stroke="white" stroke-width="2.5" stroke-linecap="round" stroke-linejoin="round"/>
+    </svg>"##).unwrap();
+    primitives.extend(heart.primitives([960.0, 560.0], [96.0, 96.0], red));
+    primitives.extend(badge.primitives([1070.0, 560.0], [96.0, 96.0], white));
+
+    // A glossy button, a glow and a progress ring, filled from the gradient table
+    let gradients = vec!(
+        Gradient::linear(quarter_turn, &[(0.0, [0.45, 0.65, 1.0, 1.0]), (0.5, [0.2, 0.4, 0.9, 1.0]), (1.0, [0.1, 0.2, 0.6, 1.0])]),
+        Gradient::radial([0.5, 0.5], 0.5, &[(0.0, [1.0, 0.9, 0.5, 1.0]), (0.4, [0.95, 0.6, 0.2, 0.8]), (1.0, [0.9, 0.2, 0.2, 0.0])]),
+        Gradient::conic([0.5, 0.5], -quarter_turn, &[(0.0, [0.2, 0.8, 0.4, 1.0]), (0.7, [0.9, 0.9, 0.2, 1.0]), (0.7, white), (1.0, white)])
+    );
+    primitives.extend(vec!(
+        RenderPrimitive::rectangle([1020.0, 730.0], [160.0, 56.0], white, black, 2.0, [0.5; 4]).with_gradient(0),
+        RenderPrimitive::circle([1170.0, 730.0], 100.0, white, white, 0.0).with_gradient(1),
+        RenderPrimitive::arc([1270.0, 730.0], 90.0, 0.0, 4.0 * quarter_turn, 14.0, white, black, 1.0).with_gradient(2),
+    ));
 
-    let font_buffer = glium::texture::texture2d_array::Texture2dArray::new(&display, glyphs).unwrap();
-    
-    println!("made raw glyph texture array of count {}", glyph_count);
+    let mut renderer = SdfRenderer::with_font_atlas(&display, &font_atlas);
+    renderer.set_gradients(&gradients);
+    let mut stream = PrimitiveStream::new(&display);
 
+    let mut window_metrics = WindowMetrics::of(&display);
     let mut time: f32 = -0.5;
 
     event_loop.run(move |event, _, control_flow| {
@@ -368,7 +100,10 @@ pub fn run() {
                     *control_flow = glutin::event_loop::ControlFlow::Exit;
                     return;
                 },
-                _ => return,
+                event => {
+                    window_metrics.handle_event(&display, &event);
+                    return;
+                },
             },
             glutin::event::Event::NewEvents(cause) => match cause {
                 glutin::event::StartCause::ResumeTimeReached { .. } => (),
@@ -383,24 +118,17 @@ pub fn run() {
             time = -0.5;
         }
 
-        &mut vertices.map()[2].expand_dimensions((time * 10.0) as i16);
-        
-        let uniforms = uniform! {
-            uResolution: resolution,
-            font_buffer: font_buffer.sampled().magnify_filter(glium::uniforms::MagnifySamplerFilter::Linear)
-        };
-    
-        let params = glium::DrawParameters {
-            blend: glium::Blend::alpha_blending(),
-            .. Default::default()
-        };
+        primitives[2].expand_dimensions(time * 10.0);
+        primitives[1].transform = primitives[1].transform.then(&Transform::rotation(0.01).around(primitives[1].position));
+
+        renderer.set_scale_factor(window_metrics.scale_factor);
 
         let mut target = display.draw();
         let draw_frame_start = std::time::Instant::now();
         target.clear_color(0.3, 0.3, 0.5, 1.0);
-        target.draw(&vertices, &indices, &program, &uniforms, &params).unwrap();
+        renderer.draw_stream(&mut target, &mut stream, &primitives).unwrap();
         target.finish().unwrap();
         let draw_time = std::time::Instant::now() - draw_frame_start;
-        println!("frame draw time: {:?}", draw_time);
+        println!("frame draw time: {:?}, upload throughput: {:.1} MB/s", draw_time, stream.stats().throughput());
     });
-}
\ No newline at end of file
+}
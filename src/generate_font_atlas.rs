@@ -0,0 +1,36 @@
+use rust_glium::font::GlyphStrip;
+use rust_glium::msdf::MsdfGenerator;
+
+const PIXELS_PER_EM: f32 = 64.0;
+
+/// Regenerates the atlas behind `FontAtlas::dejavu_sans`, Basic Latin and Latin-1, and the
+/// metadata behind `FontAtlas::segoe_ui`. Takes the path to DejaVuSans.ttf as the first argument.
+pub fn run() {
+    let font_path = std::env::args().nth(1).expect("usage: rust-glium <path to DejaVuSans.ttf>");
+    let generator = MsdfGenerator::open(font_path, PIXELS_PER_EM).unwrap();
+    let characters = (' '..='~').chain('\u{a0}'..='\u{ff}');
+
+    let generate_start = std::time::Instant::now();
+    generator.save_atlas(characters, "images/dejavu-sans.json", "images/dejavu-sans.png").unwrap();
+    println!("atlas generation time: {:?}", std::time::Instant::now() - generate_start);
+
+    let strip = image::open("images/segoeui-1.png").unwrap().to_rgba8();
+    std::fs::write("images/segoeui-1.json", segoe_ui_strip().metadata(&strip)).unwrap();
+}
+
+// The strip draws each glyph 64 pixels to the em with its origin 6 pixels in and 56 down its
+// 96 pixel cell, in Segoe UI's glyph order. Basic Latin runs in order from the space in cell 2,
+// past which the order needs the font's own tables to follow.
+fn segoe_ui_strip() -> GlyphStrip {
+    GlyphStrip {
+        cell_size: 96,
+        pixels_per_em: 64.0,
+        origin: [6.0, 56.0],
+        cells: (' '..='~').map(|character| (character, character as u32 - 30)).collect(),
+        // Segoe UI's own vertical metrics, which the strip has no room to show
+        ascender: 1.079,
+        descender: -0.251,
+        line_height: 1.33,
+        blank_advance: 0.274
+    }
+}
@@ -0,0 +1,147 @@
+use std::collections::{HashMap, HashSet};
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub evictions: u64,
+    /// Characters left without a layer because every layer was in use, once each a frame.
+    pub overflows: u64
+}
+
+#[derive(Copy, Clone, Debug)]
+struct CachedLayer {
+    character: char,
+    last_used: u64,
+    frame: u64
+}
+
+/// Assigns texture array layers to characters, evicting the least recently used
+/// character when every layer is taken. Layers used since the last `begin_frame`
+/// are never evicted, so glyph indices handed out during a frame stay valid until it ends.
+pub struct GlyphCache {
+    layers: Vec<CachedLayer>,
+    capacity: u16,
+    layer_of: HashMap<char, u16>,
+    overflowed: HashSet<char>,
+    clock: u64,
+    frame: u64,
+    stats: CacheStats
+}
+
+impl GlyphCache {
+    pub fn new(capacity: u16) -> Self {
+        Self {
+            layers: Vec::with_capacity(capacity as usize),
+            capacity,
+            layer_of: HashMap::new(),
+            overflowed: HashSet::new(),
+            clock: 0,
+            frame: 0,
+            stats: CacheStats::default()
+        }
+    }
+
+    pub fn begin_frame(&mut self) {
+        self.frame += 1;
+        self.overflowed.clear();
+    }
+
+    /// The layer holding `character`, marking it as used this frame.
+    pub fn get(&mut self, character: char) -> Option<u16> {
+        match self.layer_of.get(&character) {
+            Some(&layer) => {
+                self.stats.hits += 1;
+                self.mark_used(layer);
+                Some(layer)
+            },
+            None => {
+                self.stats.misses += 1;
+                None
+            }
+        }
+    }
+
+    /// Assigns a layer to `character`, returning it along with the character it was taken
+    /// from. Returns `None` when every layer is in use this frame.
+    pub fn insert(&mut self, character: char) -> Option<(u16, Option<char>)> {
+        if let Some(&layer) = self.layer_of.get(&character) {
+            self.mark_used(layer);
+            return Some((layer, None));
+        }
+
+        let entry = CachedLayer { character, last_used: 0, frame: 0 };
+        let (layer, evicted) = if self.layers.len() < self.capacity as usize {
+            self.layers.push(entry);
+            ((self.layers.len() - 1) as u16, None)
+        } else {
+            let frame = self.frame;
+            let least_recent = self.layers
+                .iter()
+                .enumerate()
+                .filter(|(_, cached)| cached.frame != frame)
+                .min_by_key(|(_, cached)| cached.last_used);
+            let (layer, least_recent) = match least_recent {
+                Some(least_recent) => least_recent,
+                None => {
+                    self.overflow(character);
+                    return None;
+                }
+            };
+
+            let evicted = least_recent.character;
+            self.layer_of.remove(&evicted);
+            self.layers[layer] = entry;
+            self.stats.evictions += 1;
+            (layer as u16, Some(evicted))
+        };
+
+        self.layer_of.insert(character, layer);
+        self.mark_used(layer);
+        Some((layer, evicted))
+    }
+
+    /// Whether every layer is in use this frame, so inserting a new character would fail.
+    pub fn is_full(&self) -> bool {
+        self.layers.len() >= self.capacity as usize && self.layers.iter().all(|cached| cached.frame == self.frame)
+    }
+
+    /// Notes that `character` found no free layer, counting it once a frame.
+    pub fn overflow(&mut self, character: char) {
+        if self.overflowed.insert(character) {
+            self.stats.overflows += 1;
+        }
+    }
+
+    /// Whether `character` has already overflowed this frame.
+    pub fn overflowed(&self, character: char) -> bool {
+        self.overflowed.contains(&character)
+    }
+
+    fn mark_used(&mut self, layer: u16) {
+        self.clock += 1;
+        let cached = &mut self.layers[layer as usize];
+        cached.last_used = self.clock;
+        cached.frame = self.frame;
+    }
+
+    pub fn len(&self) -> usize {
+        self.layers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    pub fn capacity(&self) -> u16 {
+        self.capacity
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = CacheStats::default();
+    }
+}
@@ -0,0 +1,79 @@
+/// How many colours each gradient's ramp holds, blended between on the way along.
+pub const GRADIENT_RAMP_SIZE: usize = 256;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GradientShape {
+    /// Across the primitive's quad from one corner to the opposite one, towards `angle` radians
+    /// clockwise from the x axis.
+    Linear { angle: f32 },
+    /// Out from `centre` to `radius`, both fractions of the quad, so it stretches with the quad.
+    Radial { centre: [f32; 2], radius: f32 },
+    /// Once clockwise around `centre`, a fraction of the quad, starting `angle` radians from the x axis.
+    Conic { centre: [f32; 2], angle: f32 }
+}
+
+/// A fill blending between colour stops, drawn across whichever primitives reference it with
+/// `RenderPrimitive::with_gradient`. Each is laid out over the primitive's own quad, so one
+/// gradient suits every button or bar of a kind whatever their sizes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Gradient {
+    pub shape: GradientShape,
+    /// Offsets from 0 to 1 along the gradient, in order, with their colours.
+    pub stops: Vec<(f32, [f32; 4])>
+}
+
+impl Gradient {
+    /// Stops are sorted by offset, keeping the order of those at the same offset.
+    pub fn new(shape: GradientShape, stops: &[(f32, [f32; 4])]) -> Self {
+        let mut stops = stops.to_vec();
+        stops.sort_by(|a, b| a.0.total_cmp(&b.0));
+        Self { shape, stops }
+    }
+
+    pub fn linear(angle: f32, stops: &[(f32, [f32; 4])]) -> Self {
+        Gradient::new(GradientShape::Linear { angle }, stops)
+    }
+
+    pub fn radial(centre: [f32; 2], radius: f32, stops: &[(f32, [f32; 4])]) -> Self {
+        Gradient::new(GradientShape::Radial { centre, radius }, stops)
+    }
+
+    pub fn conic(centre: [f32; 2], angle: f32, stops: &[(f32, [f32; 4])]) -> Self {
+        Gradient::new(GradientShape::Conic { centre, angle }, stops)
+    }
+
+    /// The colour `offset` of the way along, taking the nearest stop's colour beyond the first
+    /// and last. Blends with premultiplied alpha, so fading to transparent doesn't darken.
+    pub fn colour_at(&self, offset: f32) -> [f32; 4] {
+        let after = self.stops.iter().position(|stop| stop.0 > offset);
+        let (from, to) = match after {
+            None => match self.stops.last() {
+                Some(stop) => return stop.1,
+                None => return [0.0; 4]
+            },
+            Some(0) => return self.stops[0].1,
+            Some(index) => (self.stops[index - 1], self.stops[index])
+        };
+
+        let weight = (offset - from.0) / (to.0 - from.0);
+        let alpha = from.1[3] + (to.1[3] - from.1[3]) * weight;
+        if alpha <= 0.0 {
+            return [0.0; 4];
+        }
+        let channel = |index: usize| (from.1[index] * from.1[3] * (1.0 - weight) + to.1[index] * to.1[3] * weight) / alpha;
+        [channel(0), channel(1), channel(2), alpha]
+    }
+
+    /// The gradient's row of the renderers' gradient table: its shape as the kind, centre and
+    /// angle or radius, then its ramp.
+    pub(crate) fn texels(&self) -> Vec<[f32; 4]> {
+        let shape = match self.shape {
+            GradientShape::Linear { angle } => [0.0, 0.5, 0.5, angle],
+            GradientShape::Radial { centre, radius } => [1.0, centre[0], centre[1], radius],
+            GradientShape::Conic { centre, angle } => [2.0, centre[0], centre[1], angle]
+        };
+        std::iter::once(shape)
+            .chain((0..GRADIENT_RAMP_SIZE).map(|index| self.colour_at(index as f32 / (GRADIENT_RAMP_SIZE - 1) as f32)))
+            .collect()
+    }
+}
@@ -0,0 +1,179 @@
+use std::fmt;
+use std::path::Path;
+use glium::glutin;
+use glium::texture::{RawImage2d, Texture2d, UncompressedFloatFormat, MipmapsOption};
+use glium::framebuffer::SimpleFrameBuffer;
+use glium::{HeadlessRenderer, Surface};
+use crate::gradient::Gradient;
+use crate::primitive_store::PrimitiveStore;
+use crate::primitive_stream::PrimitiveStream;
+use crate::primitives::RenderPrimitive;
+use crate::sdf_renderer::SdfRenderer;
+
+#[derive(Debug)]
+pub enum HeadlessError {
+    Creation(glutin::CreationError),
+    IncompatibleOpenGl(glium::IncompatibleOpenGl),
+    Texture(glium::texture::TextureCreationError),
+    FrameBuffer(glium::framebuffer::ValidationError),
+    Draw(glium::DrawError),
+    Image(image::ImageError)
+}
+
+impl fmt::Display for HeadlessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeadlessError::Creation(error) => write!(f, "could not create headless context: {}", error),
+            HeadlessError::IncompatibleOpenGl(error) => write!(f, "incompatible OpenGL: {}", error),
+            HeadlessError::Texture(error) => write!(f, "could not create render texture: {}", error),
+            HeadlessError::FrameBuffer(error) => write!(f, "could not create frame buffer: {}", error),
+            HeadlessError::Draw(error) => write!(f, "could not draw primitives: {}", error),
+            HeadlessError::Image(error) => write!(f, "could not write image: {}", error)
+        }
+    }
+}
+
+impl std::error::Error for HeadlessError {}
+
+impl From<glutin::CreationError> for HeadlessError {
+    fn from(error: glutin::CreationError) -> Self {
+        HeadlessError::Creation(error)
+    }
+}
+
+impl From<glium::IncompatibleOpenGl> for HeadlessError {
+    fn from(error: glium::IncompatibleOpenGl) -> Self {
+        HeadlessError::IncompatibleOpenGl(error)
+    }
+}
+
+impl From<glium::texture::TextureCreationError> for HeadlessError {
+    fn from(error: glium::texture::TextureCreationError) -> Self {
+        HeadlessError::Texture(error)
+    }
+}
+
+impl From<glium::framebuffer::ValidationError> for HeadlessError {
+    fn from(error: glium::framebuffer::ValidationError) -> Self {
+        HeadlessError::FrameBuffer(error)
+    }
+}
+
+impl From<glium::DrawError> for HeadlessError {
+    fn from(error: glium::DrawError) -> Self {
+        HeadlessError::Draw(error)
+    }
+}
+
+impl From<image::ImageError> for HeadlessError {
+    fn from(error: image::ImageError) -> Self {
+        HeadlessError::Image(error)
+    }
+}
+
+#[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "netbsd", target_os = "openbsd"))]
+type EventLoopGuard = ();
+
+// Headless contexts outside of OSMesa are tied to the event loop that created them
+#[cfg(not(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "netbsd", target_os = "openbsd")))]
+type EventLoopGuard = glutin::event_loop::EventLoop<()>;
+
+#[cfg(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "netbsd", target_os = "openbsd"))]
+fn create_display(cb: glutin::ContextBuilder<glutin::NotCurrent>, size: glutin::dpi::PhysicalSize<u32>) -> Result<(HeadlessRenderer, EventLoopGuard), HeadlessError> {
+    use glutin::platform::unix::HeadlessContextExt;
+    Ok((HeadlessRenderer::new(cb.build_osmesa(size)?)?, ()))
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "dragonfly", target_os = "freebsd", target_os = "netbsd", target_os = "openbsd")))]
+fn create_display(cb: glutin::ContextBuilder<glutin::NotCurrent>, size: glutin::dpi::PhysicalSize<u32>) -> Result<(HeadlessRenderer, EventLoopGuard), HeadlessError> {
+    let event_loop = glutin::event_loop::EventLoop::new();
+    let display = HeadlessRenderer::new(cb.build_headless(&event_loop, size)?)?;
+    Ok((display, event_loop))
+}
+
+/// Renders primitive lists into an offscreen texture without opening a window.
+pub struct OffscreenRenderer {
+    display: HeadlessRenderer,
+    renderer: SdfRenderer,
+    dimensions: (u32, u32),
+    _event_loop: EventLoopGuard
+}
+
+impl OffscreenRenderer {
+    pub fn new(width: u32, height: u32) -> Result<Self, HeadlessError> {
+        let cb = glutin::ContextBuilder::new()
+            .with_gl(glutin::GlRequest::Specific(glutin::Api::OpenGl, (3, 3)))
+            .with_gl_profile(glutin::GlProfile::Core);
+
+        let (display, event_loop) = create_display(cb, glutin::dpi::PhysicalSize::new(width, height))?;
+        let renderer = SdfRenderer::new(&display);
+
+        Ok(Self {
+            display,
+            renderer,
+            dimensions: (width, height),
+            _event_loop: event_loop
+        })
+    }
+
+    pub fn dimensions(&self) -> (u32, u32) {
+        self.dimensions
+    }
+
+    /// The headless context, for creating resources such as a `PrimitiveStream` to render with.
+    pub fn display(&self) -> &HeadlessRenderer {
+        &self.display
+    }
+
+    /// Renders logical units at `scale_factor` physical pixels each, as on a HiDPI display.
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.renderer.set_scale_factor(scale_factor);
+    }
+
+    /// Like `SdfRenderer::set_gradients`.
+    pub fn set_gradients(&mut self, gradients: &[Gradient]) {
+        self.renderer.set_gradients(gradients);
+    }
+
+    pub fn render(&mut self, primitives: &[RenderPrimitive], clear_colour: [f32; 4]) -> Result<image::RgbaImage, HeadlessError> {
+        let renderer = &mut self.renderer;
+        render_with(&self.display, self.dimensions, clear_colour, |target| renderer.draw(target, primitives))
+    }
+
+    /// Like `render`, but uploads through `stream` as one frame of `SdfRenderer::draw_stream`.
+    pub fn render_stream(&mut self, stream: &mut PrimitiveStream, primitives: &[RenderPrimitive], clear_colour: [f32; 4]) -> Result<image::RgbaImage, HeadlessError> {
+        let renderer = &self.renderer;
+        render_with(&self.display, self.dimensions, clear_colour, |target| renderer.draw_stream(target, stream, primitives))
+    }
+
+    /// Like `render`, but draws the primitives kept in `store` through `SdfRenderer::draw_store`.
+    pub fn render_store(&mut self, store: &mut PrimitiveStore, clear_colour: [f32; 4]) -> Result<image::RgbaImage, HeadlessError> {
+        let renderer = &self.renderer;
+        render_with(&self.display, self.dimensions, clear_colour, |target| renderer.draw_store(target, store))
+    }
+
+    pub fn render_to_png<P: AsRef<Path>>(&mut self, primitives: &[RenderPrimitive], clear_colour: [f32; 4], path: P) -> Result<(), HeadlessError> {
+        let image = self.render(primitives, clear_colour)?;
+        image.save_with_format(path, image::ImageFormat::Png)?;
+        Ok(())
+    }
+}
+
+// Clears an offscreen texture, runs `draw` on it and reads the pixels back top row first
+fn render_with<D: FnOnce(&mut SimpleFrameBuffer<'_>) -> Result<(), glium::DrawError>>(display: &HeadlessRenderer, dimensions: (u32, u32), clear_colour: [f32; 4], draw: D) -> Result<image::RgbaImage, HeadlessError> {
+    let (width, height) = dimensions;
+    let texture = Texture2d::empty_with_format(display, UncompressedFloatFormat::U8U8U8U8, MipmapsOption::NoMipmap, width, height)?;
+
+    {
+        let mut target = SimpleFrameBuffer::new(display, &texture)?;
+        target.clear_color(clear_colour[0], clear_colour[1], clear_colour[2], clear_colour[3]);
+        draw(&mut target)?;
+    }
+
+    let pixels: RawImage2d<u8> = texture.read();
+    let image = image::RgbaImage::from_raw(pixels.width, pixels.height, pixels.data.into_owned())
+        .expect("texture read back with unexpected size");
+
+    // OpenGL rows start at the bottom of the texture
+    Ok(image::imageops::flip_vertical(&image))
+}
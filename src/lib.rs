@@ -0,0 +1,23 @@
+#[macro_use]
+extern crate glium;
+extern crate image;
+pub mod font;
+pub mod transform;
+pub(crate) mod vector;
+pub mod camera;
+pub mod primitives;
+pub mod gradient;
+pub mod stroke;
+pub mod path;
+pub mod svg;
+pub mod scene;
+pub mod primitive_store;
+pub mod primitive_stream;
+pub mod sdf_renderer;
+pub mod headless;
+pub mod cpu_renderer;
+pub mod text;
+pub mod msdf;
+pub mod glyph_cache;
+pub mod dynamic_font;
+pub mod window;
@@ -9,9 +9,13 @@ pub mod sdf_circle;
 pub mod full_sdf_rect_circle_text_render;
 pub mod buffers;
 pub mod empty_window;
+pub mod screenshot;
+pub mod generate_font_atlas;
 
 pub fn main() {
     //textured_wall::run();
     //buffers::run();
+    //screenshot::run();
+    //generate_font_atlas::run();
     triangle::run();
 }
\ No newline at end of file
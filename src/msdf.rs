@@ -0,0 +1,941 @@
+use std::ops::{Add, Mul, Neg, Sub};
+use std::path::Path;
+use crate::font::{
+    AtlasDescription, AtlasMetadata, Font, FontAtlas, FontError, FontMetrics, GlyphBounds, GlyphDescription, GlyphMetrics, YOrigin
+};
+
+// Edges closer than this angle (in radians) to a straight continuation are smooth,
+// anything sharper is a corner that needs two differently coloured edges
+const CORNER_ANGLE_THRESHOLD: f64 = 3.0;
+const CLASH_THRESHOLD: f64 = 1.001;
+const CUBIC_SEARCH_STARTS: usize = 4;
+const CUBIC_SEARCH_STEPS: usize = 4;
+// Saved atlases wrap their rows of glyphs at this width in pixels
+const ATLAS_SHEET_WIDTH: u32 = 1024;
+
+const RED: u8 = 1;
+const GREEN: u8 = 2;
+const BLUE: u8 = 4;
+const YELLOW: u8 = RED | GREEN;
+const MAGENTA: u8 = RED | BLUE;
+const CYAN: u8 = GREEN | BLUE;
+const WHITE: u8 = RED | GREEN | BLUE;
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+struct Vector {
+    x: f64,
+    y: f64
+}
+
+impl Vector {
+    fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+
+    fn length(self) -> f64 {
+        self.x.hypot(self.y)
+    }
+
+    fn normalize(self) -> Self {
+        let length = self.length();
+        if length == 0.0 {
+            Vector::new(0.0, 1.0)
+        } else {
+            Vector::new(self.x / length, self.y / length)
+        }
+    }
+
+    fn orthonormal(self) -> Self {
+        let length = self.length();
+        if length == 0.0 {
+            Vector::new(0.0, -1.0)
+        } else {
+            Vector::new(self.y / length, -self.x / length)
+        }
+    }
+
+    fn dot(self, other: Vector) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    fn cross(self, other: Vector) -> f64 {
+        self.x * other.y - self.y * other.x
+    }
+}
+
+impl Default for Vector {
+    fn default() -> Self {
+        Vector::new(0.0, 0.0)
+    }
+}
+
+impl Add for Vector {
+    type Output = Vector;
+    fn add(self, other: Vector) -> Vector {
+        Vector::new(self.x + other.x, self.y + other.y)
+    }
+}
+
+impl Sub for Vector {
+    type Output = Vector;
+    fn sub(self, other: Vector) -> Vector {
+        Vector::new(self.x - other.x, self.y - other.y)
+    }
+}
+
+impl Neg for Vector {
+    type Output = Vector;
+    fn neg(self) -> Vector {
+        Vector::new(-self.x, -self.y)
+    }
+}
+
+impl Mul<Vector> for f64 {
+    type Output = Vector;
+    fn mul(self, vector: Vector) -> Vector {
+        Vector::new(self * vector.x, self * vector.y)
+    }
+}
+
+fn non_zero_sign(value: f64) -> f64 {
+    if value > 0.0 { 1.0 } else { -1.0 }
+}
+
+/// A distance to an edge, with ties between edges broken by how directly the
+/// point faces the edge's nearest end.
+#[derive(Copy, Clone, Debug)]
+struct SignedDistance {
+    distance: f64,
+    dot: f64
+}
+
+impl SignedDistance {
+    const INFINITE: SignedDistance = SignedDistance { distance: f64::MAX, dot: 1.0 };
+
+    fn is_closer_than(&self, other: &SignedDistance) -> bool {
+        let distance = self.distance.abs();
+        let other_distance = other.distance.abs();
+        distance < other_distance || (distance == other_distance && self.dot < other.dot)
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum Segment {
+    Line([Vector; 2]),
+    Quadratic([Vector; 3]),
+    Cubic([Vector; 4])
+}
+
+impl Segment {
+    fn point(&self, t: f64) -> Vector {
+        match self {
+            Segment::Line([p0, p1]) => *p0 + t * (*p1 - *p0),
+            Segment::Quadratic([p0, p1, p2]) => {
+                let a = *p0 + t * (*p1 - *p0);
+                let b = *p1 + t * (*p2 - *p1);
+                a + t * (b - a)
+            },
+            Segment::Cubic([p0, p1, p2, p3]) => {
+                let p12 = *p1 + t * (*p2 - *p1);
+                let a = *p0 + t * (*p1 - *p0);
+                let b = *p2 + t * (*p3 - *p2);
+                let a = a + t * (p12 - a);
+                let b = p12 + t * (b - p12);
+                a + t * (b - a)
+            }
+        }
+    }
+
+    fn direction(&self, t: f64) -> Vector {
+        match self {
+            Segment::Line([p0, p1]) => *p1 - *p0,
+            Segment::Quadratic([p0, p1, p2]) => {
+                let direction = (*p1 - *p0) + t * ((*p2 - *p1) - (*p1 - *p0));
+                if direction.x == 0.0 && direction.y == 0.0 {
+                    *p2 - *p0
+                } else {
+                    direction
+                }
+            },
+            Segment::Cubic([p0, p1, p2, p3]) => {
+                let a = *p1 - *p0;
+                let b = *p2 - *p1;
+                let c = *p3 - *p2;
+                let ab = a + t * (b - a);
+                let bc = b + t * (c - b);
+                let direction = ab + t * (bc - ab);
+                if direction.x == 0.0 && direction.y == 0.0 {
+                    if t == 0.0 {
+                        return *p2 - *p0;
+                    }
+                    if t == 1.0 {
+                        return *p3 - *p1;
+                    }
+                }
+                direction
+            }
+        }
+    }
+
+    fn start(&self) -> Vector {
+        self.point(0.0)
+    }
+
+    fn end(&self) -> Vector {
+        self.point(1.0)
+    }
+
+    fn reversed(&self) -> Segment {
+        match self {
+            Segment::Line([p0, p1]) => Segment::Line([*p1, *p0]),
+            Segment::Quadratic([p0, p1, p2]) => Segment::Quadratic([*p2, *p1, *p0]),
+            Segment::Cubic([p0, p1, p2, p3]) => Segment::Cubic([*p3, *p2, *p1, *p0])
+        }
+    }
+
+    fn split_in_thirds(&self) -> [Segment; 3] {
+        match self {
+            Segment::Line([p0, p1]) => {
+                let a = self.point(1.0 / 3.0);
+                let b = self.point(2.0 / 3.0);
+                [Segment::Line([*p0, a]), Segment::Line([a, b]), Segment::Line([b, *p1])]
+            },
+            Segment::Quadratic([p0, p1, p2]) => {
+                let a = self.point(1.0 / 3.0);
+                let b = self.point(2.0 / 3.0);
+                let lerp = |from: Vector, to: Vector, t: f64| from + t * (to - from);
+                [
+                    Segment::Quadratic([*p0, lerp(*p0, *p1, 1.0 / 3.0), a]),
+                    Segment::Quadratic([a, lerp(lerp(*p0, *p1, 5.0 / 9.0), lerp(*p1, *p2, 4.0 / 9.0), 0.5), b]),
+                    Segment::Quadratic([b, lerp(*p1, *p2, 2.0 / 3.0), *p2])
+                ]
+            },
+            Segment::Cubic(_) => {
+                let first = self.sub_segment(0.0, 1.0 / 3.0);
+                let second = self.sub_segment(1.0 / 3.0, 2.0 / 3.0);
+                let third = self.sub_segment(2.0 / 3.0, 1.0);
+                [first, second, third]
+            }
+        }
+    }
+
+    // Only used for cubics, the exact control points of the curve between two parameters
+    fn sub_segment(&self, from: f64, to: f64) -> Segment {
+        let start = self.point(from);
+        let end = self.point(to);
+        let scale = to - from;
+        Segment::Cubic([start, start + scale * self.direction(from), end - scale * self.direction(to), end])
+    }
+
+    /// The signed distance from `origin` to the segment and the parameter of the nearest point.
+    /// Distances are positive to the right of the segment's direction.
+    fn signed_distance(&self, origin: Vector) -> (SignedDistance, f64) {
+        match self {
+            Segment::Line([p0, p1]) => {
+                let aq = origin - *p0;
+                let ab = *p1 - *p0;
+                let param = aq.dot(ab) / ab.dot(ab);
+                let eq = if param > 0.5 { *p1 } else { *p0 } - origin;
+                let endpoint_distance = eq.length();
+                if param > 0.0 && param < 1.0 {
+                    let ortho_distance = ab.orthonormal().dot(aq);
+                    if ortho_distance.abs() < endpoint_distance {
+                        return (SignedDistance { distance: ortho_distance, dot: 0.0 }, param);
+                    }
+                }
+                let distance = non_zero_sign(aq.cross(ab)) * endpoint_distance;
+                (SignedDistance { distance, dot: ab.normalize().dot(eq.normalize()).abs() }, param)
+            },
+            Segment::Quadratic([p0, p1, p2]) => {
+                let qa = *p0 - origin;
+                let ab = *p1 - *p0;
+                let br = *p2 - *p1 - ab;
+                let a = br.dot(br);
+                let b = 3.0 * ab.dot(br);
+                let c = 2.0 * ab.dot(ab) + qa.dot(br);
+                let d = qa.dot(ab);
+
+                let (mut min_distance, mut param) = self.endpoint_distances(origin);
+                for t in solve_cubic(a, b, c, d) {
+                    if t > 0.0 && t < 1.0 {
+                        let qe = qa + 2.0 * t * ab + (t * t) * br;
+                        let distance = qe.length();
+                        if distance <= min_distance.abs() {
+                            min_distance = non_zero_sign((ab + t * br).cross(qe)) * distance;
+                            param = t;
+                        }
+                    }
+                }
+                self.finish_distance(origin, min_distance, param)
+            },
+            Segment::Cubic([p0, p1, p2, p3]) => {
+                let qa = *p0 - origin;
+                let ab = *p1 - *p0;
+                let br = *p2 - *p1 - ab;
+                let as_ = (*p3 - *p2) - (*p2 - *p1) - br;
+
+                let (mut min_distance, mut param) = self.endpoint_distances(origin);
+                for start in 0..=CUBIC_SEARCH_STARTS {
+                    let mut t = start as f64 / CUBIC_SEARCH_STARTS as f64;
+                    let mut qe = qa + (3.0 * t) * ab + (3.0 * t * t) * br + (t * t * t) * as_;
+                    for _ in 0..CUBIC_SEARCH_STEPS {
+                        let d1 = 3.0 * ab + (6.0 * t) * br + (3.0 * t * t) * as_;
+                        let d2 = 6.0 * br + (6.0 * t) * as_;
+                        t -= qe.dot(d1) / (d1.dot(d1) + qe.dot(d2));
+                        if t <= 0.0 || t >= 1.0 {
+                            break;
+                        }
+                        qe = qa + (3.0 * t) * ab + (3.0 * t * t) * br + (t * t * t) * as_;
+                        let distance = qe.length();
+                        if distance < min_distance.abs() {
+                            min_distance = non_zero_sign(self.direction(t).cross(qe)) * distance;
+                            param = t;
+                        }
+                    }
+                }
+                self.finish_distance(origin, min_distance, param)
+            }
+        }
+    }
+
+    fn endpoint_distances(&self, origin: Vector) -> (f64, f64) {
+        let qa = self.start() - origin;
+        let start_direction = self.direction(0.0);
+        let mut min_distance = non_zero_sign(start_direction.cross(qa)) * qa.length();
+        let mut param = -qa.dot(start_direction) / start_direction.dot(start_direction);
+
+        let end_direction = self.direction(1.0);
+        let qe = self.end() - origin;
+        let distance = qe.length();
+        if distance < min_distance.abs() {
+            min_distance = non_zero_sign(end_direction.cross(qe)) * distance;
+            param = (end_direction - qe).dot(end_direction) / end_direction.dot(end_direction);
+        }
+        (min_distance, param)
+    }
+
+    fn finish_distance(&self, origin: Vector, distance: f64, param: f64) -> (SignedDistance, f64) {
+        let dot = if (0.0..=1.0).contains(&param) {
+            0.0
+        } else if param < 0.5 {
+            self.direction(0.0).normalize().dot((self.start() - origin).normalize()).abs()
+        } else {
+            self.direction(1.0).normalize().dot((self.end() - origin).normalize()).abs()
+        };
+        (SignedDistance { distance, dot }, param)
+    }
+
+    // Beyond the ends of a segment, measures against the tangent line instead so
+    // corners stay sharp in the combined channels
+    fn apply_pseudo_distance(&self, distance: &mut SignedDistance, origin: Vector, param: f64) {
+        if param < 0.0 {
+            let direction = self.direction(0.0).normalize();
+            let aq = origin - self.start();
+            if aq.dot(direction) < 0.0 {
+                let pseudo_distance = aq.cross(direction);
+                if pseudo_distance.abs() <= distance.distance.abs() {
+                    *distance = SignedDistance { distance: pseudo_distance, dot: 0.0 };
+                }
+            }
+        } else if param > 1.0 {
+            let direction = self.direction(1.0).normalize();
+            let bq = origin - self.end();
+            if bq.dot(direction) > 0.0 {
+                let pseudo_distance = bq.cross(direction);
+                if pseudo_distance.abs() <= distance.distance.abs() {
+                    *distance = SignedDistance { distance: pseudo_distance, dot: 0.0 };
+                }
+            }
+        }
+    }
+}
+
+fn solve_quadratic(a: f64, b: f64, c: f64) -> Vec<f64> {
+    if a.abs() < 1e-14 {
+        if b.abs() < 1e-14 {
+            return vec!();
+        }
+        return vec!(-c / b);
+    }
+    let discriminant = b * b - 4.0 * a * c;
+    if discriminant > 0.0 {
+        let root = discriminant.sqrt();
+        vec!((-b + root) / (2.0 * a), (-b - root) / (2.0 * a))
+    } else if discriminant == 0.0 {
+        vec!(-b / (2.0 * a))
+    } else {
+        vec!()
+    }
+}
+
+fn solve_cubic(a: f64, b: f64, c: f64, d: f64) -> Vec<f64> {
+    if a.abs() < 1e-14 {
+        return solve_quadratic(b, c, d);
+    }
+
+    let a2 = b / a;
+    let b2 = c / a;
+    let c2 = d / a;
+    let a_squared = a2 * a2;
+    let q = (a_squared - 3.0 * b2) / 9.0;
+    let r = (a2 * (2.0 * a_squared - 9.0 * b2) + 27.0 * c2) / 54.0;
+    let r_squared = r * r;
+    let q_cubed = q * q * q;
+    let offset = a2 / 3.0;
+
+    if r_squared < q_cubed {
+        let t = (r / q_cubed.sqrt()).clamp(-1.0, 1.0).acos();
+        let scale = -2.0 * q.sqrt();
+        let third = std::f64::consts::PI * 2.0 / 3.0;
+        vec!(
+            scale * (t / 3.0).cos() - offset,
+            scale * ((t + third) / 3.0).cos() - offset,
+            scale * ((t - third) / 3.0).cos() - offset
+        )
+    } else {
+        let mut big_a = -(r.abs() + (r_squared - q_cubed).sqrt()).cbrt();
+        if r < 0.0 {
+            big_a = -big_a;
+        }
+        let big_b = if big_a == 0.0 { 0.0 } else { q / big_a };
+        let mut roots = vec!((big_a + big_b) - offset);
+        let imaginary = 0.5 * 3f64.sqrt() * (big_a - big_b);
+        if imaginary.abs() < 1e-14 {
+            roots.push(-0.5 * (big_a + big_b) - offset);
+        }
+        roots
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Edge {
+    segment: Segment,
+    colour: u8
+}
+
+#[derive(Clone, Debug, Default)]
+struct Contour {
+    edges: Vec<Edge>
+}
+
+impl Contour {
+    // Twice the signed area of the contour's control polygon
+    fn winding_area(&self) -> f64 {
+        self.edges
+            .iter()
+            .map(|edge| {
+                let start = edge.segment.start();
+                let end = edge.segment.end();
+                start.cross(end)
+            })
+            .sum()
+    }
+
+    fn reverse(&mut self) {
+        self.edges.reverse();
+        for edge in &mut self.edges {
+            edge.segment = edge.segment.reversed();
+        }
+    }
+
+    fn flattened(&self) -> Vec<Vector> {
+        let mut points = vec!();
+        for edge in &self.edges {
+            let steps = match edge.segment {
+                Segment::Line(_) => 1,
+                _ => 8
+            };
+            for step in 0..steps {
+                points.push(edge.segment.point(step as f64 / steps as f64));
+            }
+        }
+        points
+    }
+}
+
+#[derive(Default)]
+struct OutlineCollector {
+    contours: Vec<Contour>,
+    current: Contour,
+    start: Vector,
+    position: Vector
+}
+
+impl OutlineCollector {
+    fn push(&mut self, segment: Segment, to: Vector) {
+        self.current.edges.push(Edge { segment, colour: WHITE });
+        self.position = to;
+    }
+
+    fn finish_contour(&mut self) {
+        if self.position != self.start {
+            self.push(Segment::Line([self.position, self.start]), self.start);
+        }
+        if !self.current.edges.is_empty() {
+            self.contours.push(std::mem::take(&mut self.current));
+        }
+    }
+}
+
+impl ttf_parser::OutlineBuilder for OutlineCollector {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.finish_contour();
+        self.start = Vector::new(x as f64, y as f64);
+        self.position = self.start;
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        let to = Vector::new(x as f64, y as f64);
+        if to != self.position {
+            self.push(Segment::Line([self.position, to]), to);
+        }
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        let to = Vector::new(x as f64, y as f64);
+        self.push(Segment::Quadratic([self.position, Vector::new(x1 as f64, y1 as f64), to]), to);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        let to = Vector::new(x as f64, y as f64);
+        self.push(Segment::Cubic([self.position, Vector::new(x1 as f64, y1 as f64), Vector::new(x2 as f64, y2 as f64), to]), to);
+    }
+
+    fn close(&mut self) {
+        self.finish_contour();
+    }
+}
+
+fn is_corner(a: Vector, b: Vector, cross_threshold: f64) -> bool {
+    a.dot(b) <= 0.0 || a.cross(b).abs() > cross_threshold
+}
+
+fn switch_colour(colour: &mut u8, seed: &mut u64, banned: u8) {
+    let combined = *colour & banned;
+    if combined == RED || combined == GREEN || combined == BLUE {
+        *colour = combined ^ WHITE;
+        return;
+    }
+    if *colour == 0 || *colour == WHITE {
+        const START: [u8; 3] = [CYAN, MAGENTA, YELLOW];
+        *colour = START[(*seed % 3) as usize];
+        *seed /= 3;
+        return;
+    }
+    let shifted = (*colour as u32) << (1 + (*seed & 1));
+    *colour = ((shifted | shifted >> 3) & WHITE as u32) as u8;
+    *seed >>= 1;
+}
+
+/// Assigns channels to edges so every corner is shared by two edges of different colours.
+fn colour_edges(contours: &mut [Contour]) {
+    let cross_threshold = CORNER_ANGLE_THRESHOLD.sin();
+    let mut seed = 0u64;
+
+    for contour in contours {
+        let mut corners = vec!();
+        if let Some(last) = contour.edges.last() {
+            let mut previous_direction = last.segment.direction(1.0);
+            for (index, edge) in contour.edges.iter().enumerate() {
+                if is_corner(previous_direction.normalize(), edge.segment.direction(0.0).normalize(), cross_threshold) {
+                    corners.push(index);
+                }
+                previous_direction = edge.segment.direction(1.0);
+            }
+        }
+
+        match corners.len() {
+            0 => {
+                for edge in &mut contour.edges {
+                    edge.colour = WHITE;
+                }
+            },
+            1 => {
+                // A teardrop, spread three colours around it
+                let mut colours = [WHITE, WHITE, WHITE];
+                switch_colour(&mut colours[0], &mut seed, 0);
+                colours[2] = colours[0];
+                switch_colour(&mut colours[2], &mut seed, 0);
+
+                let corner = corners[0];
+                let edge_count = contour.edges.len();
+                if edge_count >= 3 {
+                    for i in 0..edge_count {
+                        let position = (3.0 + 2.875 * i as f64 / (edge_count - 1) as f64 - 1.4375 + 0.5) as i32 - 3;
+                        contour.edges[(corner + i) % edge_count].colour = colours[(position + 1) as usize];
+                    }
+                } else {
+                    let mut parts = vec!();
+                    for edge in contour.edges.iter().cycle().skip(corner).take(edge_count) {
+                        parts.extend(edge.segment.split_in_thirds().iter().map(|segment| Edge { segment: *segment, colour: WHITE }));
+                    }
+                    let part_count = parts.len();
+                    for (index, part) in parts.iter_mut().enumerate() {
+                        part.colour = colours[index * 3 / part_count];
+                    }
+                    contour.edges = parts;
+                }
+            },
+            corner_count => {
+                let start = corners[0];
+                let edge_count = contour.edges.len();
+                let mut spline = 0;
+                let mut colour = WHITE;
+                switch_colour(&mut colour, &mut seed, 0);
+                let initial_colour = colour;
+                for i in 0..edge_count {
+                    let index = (start + i) % edge_count;
+                    if spline + 1 < corner_count && corners[spline + 1] == index {
+                        spline += 1;
+                        let banned = if spline == corner_count - 1 { initial_colour } else { 0 };
+                        switch_colour(&mut colour, &mut seed, banned);
+                    }
+                    contour.edges[index].colour = colour;
+                }
+            }
+        }
+    }
+}
+
+fn median(r: f64, g: f64, b: f64) -> f64 {
+    r.min(g).max(r.max(g).min(b))
+}
+
+/// Generates a multi-channel signed distance field for the contours, `to_shape`
+/// mapping pixel centres into shape space and `range` being in shape units.
+fn generate_msdf(contours: &[Contour], width: u32, height: u32, range: f64, to_shape: impl Fn(f64, f64) -> Vector) -> Vec<[f64; 3]> {
+    let mut field = Vec::with_capacity((width * height) as usize);
+    for y in 0..height {
+        for x in 0..width {
+            let point = to_shape(x as f64 + 0.5, y as f64 + 0.5);
+            let mut nearest = [(SignedDistance::INFINITE, None, 0.0); 3];
+            for edge in contours.iter().flat_map(|contour| contour.edges.iter()) {
+                let (distance, param) = edge.segment.signed_distance(point);
+                for (channel, mask) in [RED, GREEN, BLUE].iter().enumerate() {
+                    if edge.colour & mask != 0 && distance.is_closer_than(&nearest[channel].0) {
+                        nearest[channel] = (distance, Some(edge.segment), param);
+                    }
+                }
+            }
+
+            let mut pixel = [0.0; 3];
+            for (channel, (mut distance, segment, param)) in nearest.iter().copied().enumerate() {
+                if let Some(segment) = segment {
+                    segment.apply_pseudo_distance(&mut distance, point, param);
+                }
+                pixel[channel] = distance.distance / range + 0.5;
+            }
+            field.push(pixel);
+        }
+    }
+
+    correct_signs(&mut field, contours, width, height, &to_shape);
+    correct_clashes(&mut field, width, height, CLASH_THRESHOLD / (range * to_shape_scale(&to_shape)));
+    field
+}
+
+fn to_shape_scale(to_shape: &impl Fn(f64, f64) -> Vector) -> f64 {
+    1.0 / (to_shape(1.0, 0.0) - to_shape(0.0, 0.0)).length()
+}
+
+// Flips pixels whose median disagrees with a non-zero winding test of the outline
+fn correct_signs(field: &mut [[f64; 3]], contours: &[Contour], width: u32, height: u32, to_shape: &impl Fn(f64, f64) -> Vector) {
+    let polygons: Vec<Vec<Vector>> = contours.iter().map(|contour| contour.flattened()).collect();
+    for y in 0..height {
+        for x in 0..width {
+            let point = to_shape(x as f64 + 0.5, y as f64 + 0.5);
+            let inside = polygons.iter().map(|polygon| winding_number(polygon, point)).sum::<i32>() != 0;
+            let pixel = &mut field[(y * width + x) as usize];
+            let median_inside = median(pixel[0], pixel[1], pixel[2]) > 0.5;
+            if inside != median_inside {
+                for channel in pixel.iter_mut() {
+                    *channel = 1.0 - *channel;
+                }
+            }
+        }
+    }
+}
+
+fn winding_number(polygon: &[Vector], point: Vector) -> i32 {
+    let mut winding = 0;
+    for (index, start) in polygon.iter().enumerate() {
+        let end = polygon[(index + 1) % polygon.len()];
+        if start.y <= point.y {
+            if end.y > point.y && (end - *start).cross(point - *start) > 0.0 {
+                winding += 1;
+            }
+        } else if end.y <= point.y && (end - *start).cross(point - *start) < 0.0 {
+            winding -= 1;
+        }
+    }
+    winding
+}
+
+fn detect_clash(a: [f64; 3], b: [f64; 3], threshold: f64) -> bool {
+    let (mut a0, mut a1, mut a2) = (a[0], a[1], a[2]);
+    let (mut b0, mut b1, mut b2) = (b[0], b[1], b[2]);
+    if (b0 - a0).abs() < (b1 - a1).abs() {
+        std::mem::swap(&mut a0, &mut a1);
+        std::mem::swap(&mut b0, &mut b1);
+    }
+    if (b1 - a1).abs() < (b2 - a2).abs() {
+        std::mem::swap(&mut a1, &mut a2);
+        std::mem::swap(&mut b1, &mut b2);
+        if (b0 - a0).abs() < (b1 - a1).abs() {
+            std::mem::swap(&mut a0, &mut a1);
+            std::mem::swap(&mut b0, &mut b1);
+        }
+    }
+    (b1 - a1).abs() >= threshold && !(b0 == b1 && b0 == b2) && (a2 - 0.5).abs() >= (b2 - 0.5).abs()
+}
+
+// Neighbouring pixels whose channels interpolate across an edge that is not there
+// produce artifacts, those pixels fall back to a plain distance field
+fn correct_clashes(field: &mut [[f64; 3]], width: u32, height: u32, threshold: f64) {
+    let index = |x: u32, y: u32| (y * width + x) as usize;
+    let mut clashes = vec!();
+    for y in 0..height {
+        for x in 0..width {
+            let pixel = field[index(x, y)];
+            if (x > 0 && detect_clash(pixel, field[index(x - 1, y)], threshold))
+                || (x + 1 < width && detect_clash(pixel, field[index(x + 1, y)], threshold))
+                || (y > 0 && detect_clash(pixel, field[index(x, y - 1)], threshold))
+                || (y + 1 < height && detect_clash(pixel, field[index(x, y + 1)], threshold)) {
+                clashes.push(index(x, y));
+            }
+        }
+    }
+    for clash in clashes {
+        let pixel = field[clash];
+        let median = median(pixel[0], pixel[1], pixel[2]);
+        field[clash] = [median, median, median];
+    }
+}
+
+/// A glyph generated from an outline font. Whitespace has no image.
+pub struct GeneratedGlyph {
+    pub advance: f32,
+    pub plane_bounds: Option<GlyphBounds>,
+    pub image: Option<image::RgbaImage>
+}
+
+/// Generates MSDF glyphs from TrueType or OpenType fonts, matching the atlas layout
+/// of `FontAtlas`: each glyph in the top left of a fixed size layer.
+pub struct MsdfGenerator {
+    font_data: Vec<u8>,
+    pixels_per_em: f32,
+    range: f32,
+    layer_dimensions: (u32, u32)
+}
+
+impl MsdfGenerator {
+    pub fn open<P: AsRef<Path>>(path: P, pixels_per_em: f32) -> Result<Self, FontError> {
+        MsdfGenerator::new(std::fs::read(path)?, pixels_per_em)
+    }
+
+    pub fn new(font_data: Vec<u8>, pixels_per_em: f32) -> Result<Self, FontError> {
+        if !(pixels_per_em.is_finite() && pixels_per_em > 0.0) {
+            return Err(FontError::PixelsPerEm(pixels_per_em));
+        }
+
+        let range = (pixels_per_em / 8.0).max(2.0);
+        let layer_dimensions = {
+            let face = ttf_parser::Face::parse(&font_data, 0).map_err(FontError::Face)?;
+            let bounding_box = face.global_bounding_box();
+            let scale = pixels_per_em / face.units_per_em() as f32;
+            (
+                (bounding_box.width() as f32 * scale + range * 2.0).ceil() as u32,
+                (bounding_box.height() as f32 * scale + range * 2.0).ceil() as u32
+            )
+        };
+
+        Ok(Self {
+            font_data,
+            pixels_per_em,
+            range,
+            layer_dimensions
+        })
+    }
+
+    pub fn layer_dimensions(&self) -> (u32, u32) {
+        self.layer_dimensions
+    }
+
+    fn face(&self) -> ttf_parser::Face<'_> {
+        ttf_parser::Face::parse(&self.font_data, 0).unwrap()
+    }
+
+    /// A font with this generator's metrics and no glyphs yet.
+    pub fn empty_font(&self) -> Font {
+        let face = self.face();
+        let units_per_em = face.units_per_em() as f32;
+        let ascent = face.ascender() as f32 / units_per_em;
+        let descent = face.descender() as f32 / units_per_em;
+        Font::new(
+            [
+                self.layer_dimensions.0 as f32 / self.pixels_per_em,
+                self.layer_dimensions.1 as f32 / self.pixels_per_em
+            ],
+            ascent,
+            descent,
+            ascent - descent + face.line_gap() as f32 / units_per_em
+        )
+    }
+
+    pub fn generate(&self, character: char) -> Option<GeneratedGlyph> {
+        let face = self.face();
+        let glyph_id = face.glyph_index(character)?;
+        let units_per_em = face.units_per_em() as f64;
+        let advance = face.glyph_hor_advance(glyph_id).unwrap_or(0) as f32 / units_per_em as f32;
+
+        let mut collector = OutlineCollector::default();
+        let bounding_box = match face.outline_glyph(glyph_id, &mut collector) {
+            Some(bounding_box) => bounding_box,
+            None => return Some(GeneratedGlyph { advance, plane_bounds: None, image: None })
+        };
+        collector.finish_contour();
+        let mut contours = collector.contours;
+
+        // Distances are positive inside clockwise contours
+        if contours.iter().map(|contour| contour.winding_area()).sum::<f64>() > 0.0 {
+            for contour in &mut contours {
+                contour.reverse();
+            }
+        }
+        colour_edges(&mut contours);
+
+        let pixels_per_em = self.pixels_per_em as f64;
+        let padding = self.range as f64 / pixels_per_em;
+        let left = bounding_box.x_min as f64 / units_per_em - padding;
+        let top = bounding_box.y_max as f64 / units_per_em + padding;
+        let width = (((bounding_box.x_max - bounding_box.x_min) as f64 / units_per_em + padding * 2.0) * pixels_per_em).ceil() as u32;
+        let height = (((bounding_box.y_max - bounding_box.y_min) as f64 / units_per_em + padding * 2.0) * pixels_per_em).ceil() as u32;
+        let width = width.min(self.layer_dimensions.0);
+        let height = height.min(self.layer_dimensions.1);
+
+        let to_shape = |x: f64, y: f64| Vector::new(
+            (left + x / pixels_per_em) * units_per_em,
+            (top - y / pixels_per_em) * units_per_em
+        );
+        let range = self.range as f64 / pixels_per_em * units_per_em;
+        let field = generate_msdf(&contours, width, height, range, to_shape);
+
+        let mut image = image::RgbaImage::from_pixel(self.layer_dimensions.0, self.layer_dimensions.1, image::Rgba([0, 0, 0, 255]));
+        for (index, pixel) in field.iter().enumerate() {
+            let x = index as u32 % width;
+            let y = index as u32 / width;
+            let to_u8 = |value: f64| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+            image.put_pixel(x, y, image::Rgba([to_u8(pixel[0]), to_u8(pixel[1]), to_u8(pixel[2]), 255]));
+        }
+
+        let plane_bounds = GlyphBounds {
+            left: left as f32,
+            bottom: (top - height as f64 / pixels_per_em) as f32,
+            right: (left + width as f64 / pixels_per_em) as f32,
+            top: top as f32
+        };
+
+        Some(GeneratedGlyph { advance, plane_bounds: Some(plane_bounds), image: Some(image) })
+    }
+
+    /// Generates a static atlas holding the given characters.
+    pub fn atlas<I: IntoIterator<Item = char>>(&self, characters: I) -> FontAtlas {
+        let mut font = self.empty_font();
+        let mut layers = vec!();
+        for character in characters {
+            if font.glyph(character).is_some() {
+                continue;
+            }
+            if let Some(glyph) = self.generate(character) {
+                let glyph_index = glyph.image.map(|image| {
+                    layers.push(image);
+                    (layers.len() - 1) as u16
+                });
+                font.insert_glyph(character, GlyphMetrics { glyph_index, advance: glyph.advance, plane_bounds: glyph.plane_bounds });
+            }
+        }
+        FontAtlas { font, layers }
+    }
+
+    /// Generates the given characters packed into one image, with metadata in the msdf-atlas-gen
+    /// JSON layout that `FontAtlas::load` reads.
+    pub fn atlas_sheet<I: IntoIterator<Item = char>>(&self, characters: I) -> (String, image::RgbaImage) {
+        let mut glyphs: Vec<(char, GeneratedGlyph)> = vec!();
+        for character in characters {
+            if glyphs.iter().any(|(generated, _)| *generated == character) {
+                continue;
+            }
+            if let Some(glyph) = self.generate(character) {
+                glyphs.push((character, glyph));
+            }
+        }
+
+        // Rows of glyphs left to right, each as tall as its tallest glyph
+        let mut rects = vec!();
+        let (mut x, mut y, mut row_height, mut sheet_width) = (0, 0, 0, 1);
+        for (_, glyph) in &glyphs {
+            let rect = glyph.plane_bounds.map(|bounds| {
+                let width = ((bounds.right - bounds.left) * self.pixels_per_em).round() as u32;
+                let height = ((bounds.top - bounds.bottom) * self.pixels_per_em).round() as u32;
+                if x > 0 && x + width > ATLAS_SHEET_WIDTH {
+                    x = 0;
+                    y += row_height;
+                    row_height = 0;
+                }
+                let rect = (x, y, width, height);
+                x += width;
+                row_height = row_height.max(height);
+                sheet_width = sheet_width.max(x);
+                rect
+            });
+            rects.push(rect);
+        }
+        let sheet_height = (y + row_height).max(1);
+
+        let mut atlas_image = image::RgbaImage::from_pixel(sheet_width, sheet_height, image::Rgba([0, 0, 0, 255]));
+        for ((_, glyph), rect) in glyphs.iter().zip(&rects) {
+            if let (Some(image), Some((x, y, width, height))) = (&glyph.image, rect) {
+                image::imageops::replace(&mut atlas_image, &image::imageops::crop_imm(image, 0, 0, *width, *height).to_image(), *x, *y);
+            }
+        }
+
+        let font = self.empty_font();
+        let metadata = AtlasMetadata {
+            atlas: AtlasDescription {
+                kind: "msdf".to_string(),
+                distance_range: self.range,
+                size: self.pixels_per_em,
+                width: sheet_width,
+                height: sheet_height,
+                y_origin: YOrigin::Top
+            },
+            metrics: FontMetrics {
+                em_size: 1.0,
+                line_height: font.line_height,
+                ascender: font.ascent,
+                descender: font.descent
+            },
+            glyphs: glyphs.iter().zip(&rects).map(|((character, glyph), rect)| GlyphDescription {
+                unicode: *character as u32,
+                advance: glyph.advance,
+                plane_bounds: glyph.plane_bounds,
+                atlas_bounds: rect.map(|(x, y, width, height)| GlyphBounds {
+                    left: x as f32,
+                    bottom: (y + height) as f32,
+                    right: (x + width) as f32,
+                    top: y as f32
+                })
+            }).collect()
+        };
+
+        (serde_json::to_string_pretty(&metadata).unwrap(), atlas_image)
+    }
+
+    /// Writes `atlas_sheet` to a metadata file and an image for `FontAtlas::open`.
+    pub fn save_atlas<I: IntoIterator<Item = char>, P: AsRef<Path>, Q: AsRef<Path>>(&self, characters: I, metadata_path: P, atlas_image_path: Q) -> Result<(), FontError> {
+        let (metadata, atlas_image) = self.atlas_sheet(characters);
+        std::fs::write(metadata_path, metadata)?;
+        atlas_image.save(atlas_image_path)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,422 @@
+use std::cmp::Ordering;
+use crate::primitives::{RenderPrimitive, MAX_PATH_EXTENT};
+use crate::stroke::{self, StrokeStyle, DEFAULT_TOLERANCE};
+use crate::transform::Transform;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum FillRule {
+    /// Inside wherever the outline winds around a point other than zero times.
+    NonZero,
+    /// Inside wherever the outline crosses an odd number of times on the way out.
+    EvenOdd
+}
+
+impl FillRule {
+    fn is_inside(self, winding: i32) -> bool {
+        match self {
+            FillRule::NonZero => winding != 0,
+            FillRule::EvenOdd => winding % 2 != 0
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PathSegment {
+    MoveTo([f32; 2]),
+    LineTo([f32; 2]),
+    QuadTo([f32; 2], [f32; 2]),
+    CubicTo([f32; 2], [f32; 2], [f32; 2]),
+    Close
+}
+
+/// An outline of lines and Bezier curves in logical pixels, built up like a 2D canvas path.
+/// Fills tessellate into triangles drawn with the other primitives, so they layer in list order.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Path {
+    segments: Vec<PathSegment>
+}
+
+impl Path {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Starts a new subpath at `point`.
+    pub fn move_to(&mut self, point: [f32; 2]) -> &mut Self {
+        self.segments.push(PathSegment::MoveTo(point));
+        self
+    }
+
+    /// Drawing with no subpath started starts one at `point` instead.
+    pub fn line_to(&mut self, point: [f32; 2]) -> &mut Self {
+        self.push_drawing(PathSegment::LineTo(point), point)
+    }
+
+    pub fn quad_to(&mut self, control: [f32; 2], point: [f32; 2]) -> &mut Self {
+        self.push_drawing(PathSegment::QuadTo(control, point), point)
+    }
+
+    pub fn cubic_to(&mut self, control_1: [f32; 2], control_2: [f32; 2], point: [f32; 2]) -> &mut Self {
+        self.push_drawing(PathSegment::CubicTo(control_1, control_2, point), point)
+    }
+
+    /// Joins the subpath back to its start. Fills close every subpath whether or not it was closed.
+    pub fn close(&mut self) -> &mut Self {
+        if self.current_point().is_some() {
+            self.segments.push(PathSegment::Close);
+        }
+        self
+    }
+
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.segments.is_empty()
+    }
+
+    /// Each subpath as the points of lines within `tolerance` pixels of its curves, and whether it was closed.
+    pub fn flatten(&self, tolerance: f32) -> Vec<(Vec<[f32; 2]>, bool)> {
+        let mut subpaths: Vec<(Vec<[f32; 2]>, bool)> = vec!();
+        for segment in &self.segments {
+            if let PathSegment::MoveTo(point) = segment {
+                subpaths.push((vec!(*point), false));
+                continue;
+            }
+
+            let closed_start = match subpaths.last() {
+                Some((points, closed)) => if *closed { Some(points[0]) } else { None },
+                None => continue
+            };
+            if let Some(start) = closed_start {
+                // Drawing on after closing carries on from the start of the closed subpath
+                subpaths.push((vec!(start), false));
+            }
+            let (points, closed) = subpaths.last_mut().unwrap();
+            let from = *points.last().unwrap();
+            match *segment {
+                PathSegment::LineTo(point) => points.push(point),
+                PathSegment::QuadTo(control, point) => {
+                    points.extend(stroke::flatten_quadratic(from, control, point, tolerance).into_iter().skip(1))
+                },
+                PathSegment::CubicTo(control_1, control_2, point) => {
+                    points.extend(stroke::flatten_cubic(from, control_1, control_2, point, tolerance).into_iter().skip(1))
+                },
+                PathSegment::Close => *closed = true,
+                PathSegment::MoveTo(_) => unreachable!()
+            }
+        }
+        subpaths
+    }
+
+    /// The same outline with every point mapped through `transform`, which curves follow exactly.
+    pub fn transformed(&self, transform: &Transform) -> Self {
+        let segments = self.segments.iter().map(|segment| match *segment {
+            PathSegment::MoveTo(point) => PathSegment::MoveTo(transform.apply(point)),
+            PathSegment::LineTo(point) => PathSegment::LineTo(transform.apply(point)),
+            PathSegment::QuadTo(control, point) => PathSegment::QuadTo(transform.apply(control), transform.apply(point)),
+            PathSegment::CubicTo(control_1, control_2, point) => {
+                PathSegment::CubicTo(transform.apply(control_1), transform.apply(control_2), transform.apply(point))
+            },
+            PathSegment::Close => PathSegment::Close
+        }).collect();
+        Self { segments }
+    }
+
+    /// Tessellates the path's inside into anti-aliased triangles. Their edges fade out over the
+    /// pixel inside the outline, so fills come out about half a pixel smaller than the path.
+    pub fn fill(&self, fill_rule: FillRule, colour: [f32; 4]) -> Vec<RenderPrimitive> {
+        self.fill_with_tolerance(fill_rule, colour, DEFAULT_TOLERANCE)
+    }
+
+    /// `fill`, flattening curves to within `tolerance` logical pixels, tighter for paths drawn magnified.
+    pub fn fill_with_tolerance(&self, fill_rule: FillRule, colour: [f32; 4], tolerance: f32) -> Vec<RenderPrimitive> {
+        let rings: Vec<Vec<[f32; 2]>> = self.flatten(tolerance).into_iter().map(|(points, _)| points).collect();
+        let triangles = tessellate(&rings, fill_rule);
+        if triangles.is_empty() {
+            return vec!();
+        }
+
+        // Every triangle shares the path's centre, so the vertices they share match exactly on the GPU
+        let min = |axis: usize| triangles.iter().flat_map(|triangle| triangle.0.iter()).map(|point| point[axis]).fold(f32::INFINITY, f32::min);
+        let max = |axis: usize| triangles.iter().flat_map(|triangle| triangle.0.iter()).map(|point| point[axis]).fold(f32::NEG_INFINITY, f32::max);
+        let dimensions = [max(0) - min(0), max(1) - min(1)];
+        let centre = [min(0) + dimensions[0] / 2.0, min(1) + dimensions[1] / 2.0];
+
+        if dimensions[0].max(dimensions[1]) <= MAX_PATH_EXTENT {
+            return triangles.into_iter()
+                .filter_map(|(vertices, outline)| RenderPrimitive::path_triangle(centre, dimensions, vertices, outline, colour))
+                .collect();
+        }
+
+        // Larger paths are cut into tiles on a grid of multiples of the extent. Each tile is its
+        // triangles' quad, and the vertices on its edges sit exactly half a tile from its centre,
+        // so they still match those of the tile beside it.
+        let tile = |value: f32| (value / MAX_PATH_EXTENT).floor() as i64;
+        let mut primitives = vec!();
+        for (vertices, outline) in triangles {
+            let first = |axis: usize| tile(vertices.iter().map(|vertex| vertex[axis]).fold(f32::INFINITY, f32::min));
+            let last = |axis: usize| tile(vertices.iter().map(|vertex| vertex[axis]).fold(f32::NEG_INFINITY, f32::max));
+            for row in first(1)..=last(1) {
+                for column in first(0)..=last(0) {
+                    let bounds = [column as f32 * MAX_PATH_EXTENT, row as f32 * MAX_PATH_EXTENT];
+                    let centre = [bounds[0] + MAX_PATH_EXTENT / 2.0, bounds[1] + MAX_PATH_EXTENT / 2.0];
+                    let clipped = clip_to_tile(&vertices, outline, bounds);
+                    primitives.extend(fan(&clipped).into_iter().filter_map(|(vertices, outline)| {
+                        RenderPrimitive::path_triangle(centre, [MAX_PATH_EXTENT; 2], vertices, outline, colour)
+                    }));
+                }
+            }
+        }
+        primitives
+    }
+
+    /// Strokes every subpath, flattening the curves that one primitive can't draw.
+    pub fn stroke(&self, style: &StrokeStyle) -> Vec<RenderPrimitive> {
+        self.flatten(style.tolerance)
+            .iter()
+            .flat_map(|(points, closed)| stroke::stroke_polyline(points, *closed, style))
+            .collect()
+    }
+
+    fn current_point(&self) -> Option<[f32; 2]> {
+        match *self.segments.last()? {
+            PathSegment::MoveTo(point) | PathSegment::LineTo(point) | PathSegment::QuadTo(_, point) | PathSegment::CubicTo(_, _, point) => Some(point),
+            // Back at the start of the subpath
+            PathSegment::Close => self.segments.iter().rev().find_map(|segment| match *segment {
+                PathSegment::MoveTo(point) => Some(point),
+                _ => None
+            })
+        }
+    }
+
+    fn push_drawing(&mut self, segment: PathSegment, point: [f32; 2]) -> &mut Self {
+        if self.current_point().is_none() {
+            return self.move_to(point);
+        }
+        self.segments.push(segment);
+        self
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Edge {
+    top: [f32; 2],
+    bottom: [f32; 2],
+    winding: i32
+}
+
+impl Edge {
+    // Exact at the ends, so bands meeting there share their corners
+    fn x_at(&self, y: f32) -> f32 {
+        if y == self.top[1] {
+            self.top[0]
+        } else if y == self.bottom[1] {
+            self.bottom[0]
+        } else {
+            self.top[0] + (self.bottom[0] - self.top[0]) * (y - self.top[1]) / (self.bottom[1] - self.top[1])
+        }
+    }
+}
+
+// Where the fill runs across a band, from its left edge to its right, at the band's top and bottom
+#[derive(Copy, Clone, Debug)]
+struct Span {
+    top: [f32; 2],
+    bottom: [f32; 2]
+}
+
+/// Triangles covering the inside of closed `rings`, each with bits marking which of its edges,
+/// AB, BC and CA in turn, lie on the outline and fade out.
+///
+/// Cuts the fill into horizontal bands at every vertex and crossing, so each band's inside runs
+/// between pairs of edges. Those trapezoids stack exactly, and only their sides and the parts of
+/// their tops and bottoms with nothing filled beyond are on the outline.
+pub fn tessellate(rings: &[Vec<[f32; 2]>], fill_rule: FillRule) -> Vec<([[f32; 2]; 3], u8)> {
+    let edges: Vec<Edge> = rings.iter()
+        .flat_map(|ring| (0..ring.len()).map(move |index| (ring[index], ring[(index + 1) % ring.len()])))
+        .filter(|(from, to)| from[1] != to[1])
+        .map(|(from, to)| if from[1] < to[1] {
+            Edge { top: from, bottom: to, winding: 1 }
+        } else {
+            Edge { top: to, bottom: from, winding: -1 }
+        })
+        .collect();
+
+    let mut band_edges: Vec<f32> = edges.iter().flat_map(|edge| [edge.top[1], edge.bottom[1]]).collect();
+    for (index, first) in edges.iter().enumerate() {
+        for second in &edges[index + 1..] {
+            if let Some(y) = crossing(first, second) {
+                band_edges.push(y);
+            }
+        }
+    }
+    band_edges.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    band_edges.dedup();
+
+    let bands: Vec<(f32, f32, Vec<Span>)> = band_edges.windows(2)
+        .map(|ys| (ys[0], ys[1], band_spans(&edges, ys[0], ys[1], fill_rule)))
+        .collect();
+
+    let mut triangles = vec!();
+    for (index, (_, _, spans)) in bands.iter().enumerate() {
+        let above: Vec<[f32; 2]> = match index.checked_sub(1).map(|above| &bands[above]) {
+            Some((_, bottom, above_spans)) if *bottom == bands[index].0 => above_spans.iter().map(|span| span.bottom).collect(),
+            _ => vec!()
+        };
+        let below: Vec<[f32; 2]> = match bands.get(index + 1) {
+            Some((top, _, below_spans)) if *top == bands[index].1 => below_spans.iter().map(|span| span.top).collect(),
+            _ => vec!()
+        };
+        for span in spans {
+            trapezoid(span, bands[index].0, bands[index].1, &above, &below, &mut triangles);
+        }
+    }
+    triangles
+}
+
+// A triangle's part inside the tile with its top left at `bounds`, each corner paired with whether
+// the edge it starts is on the outline. Edges are cut the same whichever way round they run, so
+// triangles sharing an edge share the corners cut into it.
+fn clip_to_tile(vertices: &[[f32; 2]; 3], outline: u8, bounds: [f32; 2]) -> Vec<([f32; 2], bool)> {
+    let mut polygon: Vec<([f32; 2], bool)> = (0..3).map(|index| (vertices[index], outline & (1 << index) != 0)).collect();
+    for axis in 0..2 {
+        for (limit, below) in [(bounds[axis], false), (bounds[axis] + MAX_PATH_EXTENT, true)] {
+            let inside = |point: [f32; 2]| if below { point[axis] <= limit } else { point[axis] >= limit };
+            let mut clipped = vec!();
+            for index in 0..polygon.len() {
+                let ((from, on_outline), (to, _)) = (polygon[index], polygon[(index + 1) % polygon.len()]);
+                if inside(from) {
+                    clipped.push((from, on_outline));
+                }
+                if inside(from) != inside(to) {
+                    let (start, end) = if (from[0], from[1]) < (to[0], to[1]) { (from, to) } else { (to, from) };
+                    let t = (limit - start[axis]) / (end[axis] - start[axis]);
+                    let mut cut = [start[0] + t * (end[0] - start[0]), start[1] + t * (end[1] - start[1])];
+                    cut[axis] = limit;
+                    // Leaving the tile, the edge from the cut runs along its side
+                    clipped.push((cut, on_outline && !inside(from)));
+                }
+            }
+            polygon = clipped;
+        }
+    }
+    polygon
+}
+
+// Triangles fanning out from a convex polygon's first corner, with its outline bits
+fn fan(polygon: &[([f32; 2], bool)]) -> Vec<([[f32; 2]; 3], u8)> {
+    let last = polygon.len().saturating_sub(1);
+    (1..last).map(|index| {
+        let vertices = [polygon[0].0, polygon[index].0, polygon[index + 1].0];
+        let first_side = if index == 1 && polygon[0].1 { 0b001 } else { 0 };
+        let along = if polygon[index].1 { 0b010 } else { 0 };
+        let last_side = if index + 1 == last && polygon[last].1 { 0b100 } else { 0 };
+        (vertices, first_side | along | last_side)
+    })
+    .filter(|(vertices, _)| {
+        (vertices[1][0] - vertices[0][0]) * (vertices[2][1] - vertices[0][1]) != (vertices[1][1] - vertices[0][1]) * (vertices[2][0] - vertices[0][0])
+    })
+    .collect()
+}
+
+// The height where two edges cross strictly inside both
+fn crossing(first: &Edge, second: &Edge) -> Option<f32> {
+    let top = first.top[1].max(second.top[1]);
+    let bottom = first.bottom[1].min(second.bottom[1]);
+    if top >= bottom {
+        return None;
+    }
+
+    let top_gap = first.x_at(top) - second.x_at(top);
+    let bottom_gap = first.x_at(bottom) - second.x_at(bottom);
+    if top_gap * bottom_gap >= 0.0 {
+        return None;
+    }
+    let y = top + (bottom - top) * top_gap / (top_gap - bottom_gap);
+    if y > top && y < bottom { Some(y) } else { None }
+}
+
+fn band_spans(edges: &[Edge], top: f32, bottom: f32, fill_rule: FillRule) -> Vec<Span> {
+    let middle = (top + bottom) / 2.0;
+    let mut crossing: Vec<&Edge> = edges.iter().filter(|edge| edge.top[1] <= top && edge.bottom[1] >= bottom).collect();
+    crossing.sort_by(|a, b| a.x_at(middle).partial_cmp(&b.x_at(middle)).unwrap_or(Ordering::Equal));
+
+    let mut spans = vec!();
+    let mut winding = 0;
+    let mut left: Option<&Edge> = None;
+    for edge in crossing {
+        let was_inside = fill_rule.is_inside(winding);
+        winding += edge.winding;
+        match (was_inside, fill_rule.is_inside(winding)) {
+            (false, true) => left = Some(edge),
+            (true, false) => {
+                let left = left.take().unwrap();
+                spans.push(Span {
+                    top: [left.x_at(top), edge.x_at(top)],
+                    bottom: [left.x_at(bottom), edge.x_at(bottom)]
+                });
+            },
+            _ => ()
+        }
+    }
+    spans
+}
+
+// Splits a span's top or bottom where the spans beyond it start and stop, marking the pieces
+// with nothing filled beyond as outline
+fn split_side(from: f32, to: f32, beyond: &[[f32; 2]]) -> Vec<(f32, bool)> {
+    let mut cuts: Vec<f32> = beyond.iter()
+        .flat_map(|range| range.iter().copied())
+        .filter(|&x| x > from && x < to)
+        .collect();
+    cuts.push(from);
+    cuts.push(to);
+    cuts.sort_by(|a, b| a.partial_cmp(b).unwrap_or(Ordering::Equal));
+    cuts.dedup();
+
+    // Each cut, with whether the piece starting there is outline
+    cuts.windows(2)
+        .map(|piece| {
+            let middle = (piece[0] + piece[1]) / 2.0;
+            (piece[0], !beyond.iter().any(|range| range[0] <= middle && middle <= range[1]))
+        })
+        .chain(std::iter::once((to, false)))
+        .collect()
+}
+
+// Zips a span's top and bottom together into triangles
+fn trapezoid(span: &Span, top: f32, bottom: f32, above: &[[f32; 2]], below: &[[f32; 2]], triangles: &mut Vec<([[f32; 2]; 3], u8)>) {
+    let top_side = split_side(span.top[0], span.top[1], above);
+    let bottom_side = split_side(span.bottom[0], span.bottom[1], below);
+    let (last_top, last_bottom) = (top_side.len() - 1, bottom_side.len() - 1);
+    let (mut top_index, mut bottom_index) = (0, 0);
+
+    while top_index < last_top || bottom_index < last_bottom {
+        let top_point = [top_side[top_index].0, top];
+        let bottom_point = [bottom_side[bottom_index].0, bottom];
+        // The first triangle holds the span's left side and the last its right, both on the outline
+        let left_side = if top_index == 0 && bottom_index == 0 { 0b001 } else { 0 };
+
+        let advance_top = bottom_index == last_bottom ||
+            (top_index < last_top && top_side[top_index + 1].0 <= bottom_side[bottom_index + 1].0);
+        let (vertices, outline) = if advance_top {
+            let along = if top_side[top_index].1 { 0b010 } else { 0 };
+            top_index += 1;
+            let right_side = if top_index == last_top && bottom_index == last_bottom { 0b100 } else { 0 };
+            ([bottom_point, top_point, [top_side[top_index].0, top]], left_side | along | right_side)
+        } else {
+            let along = if bottom_side[bottom_index].1 { 0b100 } else { 0 };
+            bottom_index += 1;
+            let right_side = if top_index == last_top && bottom_index == last_bottom { 0b010 } else { 0 };
+            ([bottom_point, top_point, [bottom_side[bottom_index].0, bottom]], left_side | along | right_side)
+        };
+
+        let area = (vertices[1][0] - vertices[0][0]) * (vertices[2][1] - vertices[0][1]) -
+            (vertices[1][1] - vertices[0][1]) * (vertices[2][0] - vertices[0][0]);
+        if area != 0.0 {
+            triangles.push((vertices, outline));
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use std::ops::Range;
+use glium::backend::Facade;
+use glium::vertex::VertexBufferSlice;
+use glium::VertexBuffer;
+use crate::primitives::{PackedPrimitive, RenderPrimitive};
+
+const INITIAL_CAPACITY: usize = 1024;
+
+/// Indices changed since the last upload, merged into contiguous ranges so each run is written once.
+#[derive(Clone, Debug, Default)]
+pub struct DirtyRanges {
+    ranges: Vec<Range<usize>>
+}
+
+impl DirtyRanges {
+    pub fn insert(&mut self, index: usize) {
+        if let Some(last) = self.ranges.last_mut() {
+            if last.contains(&index) {
+                return;
+            }
+            if last.end == index {
+                last.end += 1;
+                return;
+            }
+        }
+        self.ranges.push(index..index + 1);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.is_empty()
+    }
+
+    /// The sorted, non-overlapping ranges marked so far, clearing them.
+    pub fn take(&mut self) -> Vec<Range<usize>> {
+        let mut ranges = std::mem::take(&mut self.ranges);
+        ranges.sort_by_key(|range| range.start);
+
+        let mut merged: Vec<Range<usize>> = Vec::with_capacity(ranges.len());
+        for range in ranges {
+            match merged.last_mut() {
+                Some(last) if range.start <= last.end => last.end = last.end.max(range.end),
+                _ => merged.push(range)
+            }
+        }
+        merged
+    }
+}
+
+/// Refers to one primitive in a `PrimitiveStore` for as long as it is not removed.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct PrimitiveHandle {
+    slot: u32,
+    generation: u32
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Slot {
+    generation: u32,
+    index: Option<usize>
+}
+
+/// Primitives kept in a vertex buffer between frames, addressed by stable handles.
+///
+/// Primitives draw in the order they were added. Removing one leaves a hidden gap until
+/// `compact` closes the gaps, which `upload` does by itself once they outnumber the live
+/// primitives. Changes are recorded as dirty ranges and written by `upload`, one write per
+/// range, growing the vertex buffer when it is too small.
+pub struct PrimitiveStore {
+    vertices: Option<VertexBuffer<PackedPrimitive>>,
+    packed: Vec<PackedPrimitive>,
+    primitives: Vec<RenderPrimitive>,
+    owners: Vec<Option<u32>>,
+    slots: Vec<Slot>,
+    free_slots: Vec<u32>,
+    gaps: usize,
+    dirty: DirtyRanges
+}
+
+impl Default for PrimitiveStore {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PrimitiveStore {
+    pub fn new() -> Self {
+        Self {
+            vertices: None,
+            packed: vec!(),
+            primitives: vec!(),
+            owners: vec!(),
+            slots: vec!(),
+            free_slots: vec!(),
+            gaps: 0,
+            dirty: DirtyRanges::default()
+        }
+    }
+
+    pub fn add(&mut self, primitive: RenderPrimitive) -> PrimitiveHandle {
+        let index = self.primitives.len();
+        let slot = match self.free_slots.pop() {
+            Some(slot) => slot,
+            None => {
+                self.slots.push(Slot { generation: 0, index: None });
+                (self.slots.len() - 1) as u32
+            }
+        };
+
+        self.slots[slot as usize].index = Some(index);
+        self.primitives.push(primitive);
+        self.owners.push(Some(slot));
+        self.dirty.insert(index);
+        PrimitiveHandle { slot, generation: self.slots[slot as usize].generation }
+    }
+
+    pub fn get(&self, handle: PrimitiveHandle) -> Option<&RenderPrimitive> {
+        self.index_of(handle).map(|index| &self.primitives[index])
+    }
+
+    /// Replaces the primitive, returning false if the handle has been removed.
+    pub fn update(&mut self, handle: PrimitiveHandle, primitive: RenderPrimitive) -> bool {
+        match self.index_of(handle) {
+            Some(index) => {
+                self.primitives[index] = primitive;
+                self.dirty.insert(index);
+                true
+            },
+            None => false
+        }
+    }
+
+    pub fn remove(&mut self, handle: PrimitiveHandle) -> Option<RenderPrimitive> {
+        let index = self.index_of(handle)?;
+        let slot = &mut self.slots[handle.slot as usize];
+        slot.generation = slot.generation.wrapping_add(1);
+        slot.index = None;
+        self.free_slots.push(handle.slot);
+
+        let removed = std::mem::replace(&mut self.primitives[index], hidden());
+        self.owners[index] = None;
+        self.gaps += 1;
+        self.dirty.insert(index);
+
+        // Gaps at the end need not be drawn at all
+        while let Some(None) = self.owners.last() {
+            self.owners.pop();
+            self.primitives.pop();
+            self.gaps -= 1;
+        }
+        Some(removed)
+    }
+
+    fn index_of(&self, handle: PrimitiveHandle) -> Option<usize> {
+        match self.slots.get(handle.slot as usize) {
+            Some(slot) if slot.generation == handle.generation => slot.index,
+            _ => None
+        }
+    }
+
+    /// The number of primitives that have not been removed.
+    pub fn len(&self) -> usize {
+        self.primitives.len() - self.gaps
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// The primitives as they are laid out in the vertex buffer, removed ones hidden.
+    pub fn primitives(&self) -> &[RenderPrimitive] {
+        &self.primitives
+    }
+
+    /// Closes the gaps left by removed primitives, keeping the draw order.
+    pub fn compact(&mut self) {
+        if self.gaps == 0 {
+            return;
+        }
+
+        let first_gap = self.owners.iter().position(Option::is_none).unwrap_or(0);
+        let mut kept = first_gap;
+        for index in first_gap..self.primitives.len() {
+            if let Some(slot) = self.owners[index] {
+                self.primitives[kept] = self.primitives[index];
+                self.owners[kept] = Some(slot);
+                self.slots[slot as usize].index = Some(kept);
+                kept += 1;
+            }
+        }
+        self.primitives.truncate(kept);
+        self.owners.truncate(kept);
+        self.gaps = 0;
+
+        for index in first_gap..kept {
+            self.dirty.insert(index);
+        }
+    }
+
+    /// Compacts and reallocates the vertex buffer to fit, releasing memory after many removals.
+    pub fn shrink_to_fit(&mut self) {
+        self.compact();
+        self.primitives.shrink_to_fit();
+        self.owners.shrink_to_fit();
+        if self.vertices.as_ref().is_some_and(|vertices| vertices.len() > buffer_capacity(self.primitives.len())) {
+            self.vertices = None;
+        }
+    }
+
+    /// Writes the changes since the last upload to the vertex buffer, returning how many writes it took.
+    pub fn upload<F: Facade + ?Sized>(&mut self, facade: &F) -> usize {
+        if self.gaps > self.len() {
+            self.compact();
+        }
+
+        let required = self.primitives.len();
+        if self.vertices.as_ref().is_none_or(|vertices| vertices.len() < required) {
+            self.vertices = Some(VertexBuffer::empty_dynamic(facade, buffer_capacity(required)).unwrap());
+            self.dirty.take();
+            for index in 0..required {
+                self.dirty.insert(index);
+            }
+        }
+
+        let vertices = self.vertices.as_ref().unwrap();
+        let ranges = self.dirty.take();
+        let mut writes = 0;
+        for range in ranges {
+            // Ranges past the end belong to primitives removed since
+            let range = range.start..range.end.min(required);
+            if !range.is_empty() {
+                self.packed.clear();
+                self.packed.extend(self.primitives[range.clone()].iter().map(RenderPrimitive::pack));
+                vertices.slice(range).unwrap().write(&self.packed);
+                writes += 1;
+            }
+        }
+        writes
+    }
+
+    /// The vertex buffer's length in primitives, zero before the first `upload`.
+    pub fn capacity(&self) -> usize {
+        self.vertices.as_ref().map_or(0, |vertices| vertices.len())
+    }
+
+    /// The uploaded primitives, for drawing as points. `None` before the first `upload`.
+    pub fn vertices(&self) -> Option<VertexBufferSlice<'_, PackedPrimitive>> {
+        self.vertices.as_ref().and_then(|vertices| vertices.slice(0..self.primitives.len()))
+    }
+}
+
+fn buffer_capacity(required: usize) -> usize {
+    required.max(INITIAL_CAPACITY).next_power_of_two()
+}
+
+// A zero sized quad covers no pixels
+fn hidden() -> RenderPrimitive {
+    RenderPrimitive::with_position_size_colours_identification_and_data([0.0, 0.0], [0.0, 0.0], [0.0; 4], [0.0; 4], [0, 0], [0.0; 4], [0.0; 4])
+}
@@ -0,0 +1,104 @@
+use std::rc::Rc;
+use std::time::{Duration, Instant};
+use glium::backend::{Context, Facade};
+use glium::vertex::VertexBufferSlice;
+use glium::VertexBuffer;
+use crate::primitives::{PackedPrimitive, RenderPrimitive};
+
+const FRAMES_IN_FLIGHT: usize = 3;
+const INITIAL_CAPACITY: usize = 1024;
+
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct StreamStats {
+    pub frames: u64,
+    pub primitives: u64,
+    pub bytes: u64,
+    pub upload_time: Duration
+}
+
+impl StreamStats {
+    /// Sustained upload rate in megabytes per second.
+    pub fn throughput(&self) -> f64 {
+        let seconds = self.upload_time.as_secs_f64();
+        if seconds == 0.0 {
+            0.0
+        } else {
+            self.bytes as f64 / seconds / 1_000_000.0
+        }
+    }
+}
+
+/// Uploads a fresh primitive list every frame without waiting on the GPU. Frames rotate through
+/// three persistently mapped buffers and pack straight into the mapping. Glium fences each buffer
+/// after it is drawn, so a write only waits when its buffer comes round again still in use.
+///
+/// Each frame, `write` the primitives, draw `vertices()`, then call `end_frame`.
+pub struct PrimitiveStream {
+    context: Rc<Context>,
+    buffers: Vec<VertexBuffer<PackedPrimitive>>,
+    current: usize,
+    len: usize,
+    stats: StreamStats
+}
+
+impl PrimitiveStream {
+    pub fn new<F: Facade + ?Sized>(facade: &F) -> Self {
+        Self {
+            context: facade.get_context().clone(),
+            buffers: (0..FRAMES_IN_FLIGHT).map(|_| create_buffer(facade, INITIAL_CAPACITY)).collect(),
+            current: 0,
+            len: 0,
+            stats: StreamStats::default()
+        }
+    }
+
+    /// Writes this frame's primitives into the next buffer in the rotation.
+    pub fn write(&mut self, primitives: &[RenderPrimitive]) {
+        let upload_start = Instant::now();
+
+        if self.buffers[self.current].len() < primitives.len() {
+            self.buffers[self.current] = create_buffer(&self.context, primitives.len().next_power_of_two());
+        }
+        if !primitives.is_empty() {
+            // Glium fences a persistent buffer after every draw that reads it, and `map_write` waits
+            // on that fence. With three buffers in the ring, writing frame N + 1 only waits if the
+            // GPU is still reading the same buffer from frame N + 1 - FRAMES_IN_FLIGHT, so frame N's
+            // draw is never stalled on and no fences of our own are needed.
+            let mut mapping = self.buffers[self.current].slice_mut(0..primitives.len()).unwrap().map_write();
+            for (index, primitive) in primitives.iter().enumerate() {
+                mapping.set(index, primitive.pack());
+            }
+        }
+        self.len = primitives.len();
+
+        self.stats.frames += 1;
+        self.stats.primitives += primitives.len() as u64;
+        self.stats.bytes += (primitives.len() * std::mem::size_of::<PackedPrimitive>()) as u64;
+        self.stats.upload_time += upload_start.elapsed();
+    }
+
+    /// The primitives written this frame.
+    pub fn vertices(&self) -> VertexBufferSlice<'_, PackedPrimitive> {
+        self.buffers[self.current].slice(0..self.len).unwrap()
+    }
+
+    /// Moves on to the next buffer once this frame's primitives have been drawn.
+    pub fn end_frame(&mut self) {
+        self.current = (self.current + 1) % FRAMES_IN_FLIGHT;
+    }
+
+    pub fn stats(&self) -> StreamStats {
+        self.stats
+    }
+
+    pub fn reset_stats(&mut self) {
+        self.stats = StreamStats::default();
+    }
+}
+
+// Falls back to a dynamic buffer where persistent mapping is unsupported
+fn create_buffer<F: Facade + ?Sized>(facade: &F, capacity: usize) -> VertexBuffer<PackedPrimitive> {
+    VertexBuffer::empty_persistent(facade, capacity)
+        .or_else(|_| VertexBuffer::empty_dynamic(facade, capacity))
+        .unwrap()
+}
@@ -0,0 +1,493 @@
+use std::f32::consts::{PI, TAU};
+use crate::transform::Transform;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LineCap {
+    /// Ends flush with the end points.
+    Butt,
+    /// Extends half the width past the end points.
+    Square,
+    /// A half circle around each end point.
+    Round
+}
+
+impl LineCap {
+    // How the shaders tell the ends of strokes apart, see `stroke::LineJoin::code`
+    pub(crate) fn code(self) -> f32 {
+        match self {
+            LineCap::Butt => 0.0,
+            LineCap::Square => 1.0,
+            LineCap::Round => 2.0
+        }
+    }
+}
+
+/// The widest or tallest quad a curve may take. Its points are stored as half float fractions
+/// of the quad, which drift by a quarter pixel past this.
+pub const MAX_CURVE_EXTENT: f32 = 1024.0;
+
+/// The widest or tallest quad a path triangle may take, for the same reason as `MAX_CURVE_EXTENT`.
+pub const MAX_PATH_EXTENT: f32 = 1024.0;
+
+/// A shape drawn as one point. `transform` maps the primitive's quad, in screen pixels, to
+/// where it is drawn, while its SDF is still evaluated in the untransformed quad.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RenderPrimitive {
+    pub position: [f32; 2],
+    pub dimensions: [f32; 2],
+    pub inner_colour: [f32; 4],
+    pub outer_colour: [f32; 4],
+    pub identification: [u16; 2],
+    pub extra_data_1: [f32; 4],
+    pub extra_data_2: [f32; 4],
+    pub transform: Transform
+}
+
+impl RenderPrimitive {
+    pub fn with_position_size_colours_identification_and_data(
+        position: [f32; 2],
+        dimensions: [f32; 2],
+        inner_colour: [f32; 4],
+        outer_colour: [f32; 4],
+        identification: [u16; 2],
+        extra_data_1: [f32; 4],
+        extra_data_2: [f32; 4]) -> Self {
+        Self {
+            position,
+            dimensions,
+            inner_colour,
+            outer_colour,
+            identification,
+            extra_data_1,
+            extra_data_2,
+            transform: Transform::IDENTITY
+        }
+    }
+    
+    /// A circle `diameter` pixels across, filling a square quad of that side.
+    pub fn circle(
+        position: [f32; 2],
+        diameter: f32,
+        inner_colour: [f32; 4],
+        outer_colour: [f32; 4],
+        stroke_width: f32) -> Self {
+        RenderPrimitive::with_position_size_colours_identification_and_data(
+            position,
+            [diameter, diameter],
+            inner_colour,
+            outer_colour,
+            [0, 0],
+            [stroke_width, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0]
+        ) 
+    }
+
+    /// Corner radii run top left, top right, bottom left, bottom right, as fractions of the shorter side.
+    pub fn rectangle(
+        position: [f32; 2],
+        dimensions: [f32; 2],
+        inner_colour: [f32; 4],
+        outer_colour: [f32; 4],
+        stroke_width: f32,
+        corner_radii: [f32; 4]) -> Self {
+        RenderPrimitive::with_position_size_colours_identification_and_data(
+            position,
+            dimensions,
+            inner_colour,
+            outer_colour,
+            [1, 0],
+            [stroke_width, 0.0, 0.0, 0.0],
+            corner_radii
+        ) 
+    }
+
+    pub fn text(
+        position: [f32; 2],
+        dimensions: [f32; 2],
+        colour: [f32; 4],
+        glyph_index: u16) -> Self {
+        RenderPrimitive::with_position_size_colours_identification_and_data(
+            position,
+            dimensions,
+            colour,
+            colour,
+            [2, glyph_index],
+            [0.0, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0]
+        ) 
+    }
+
+    /// A straight line `width` pixels wide, drawn as a box turned to lie along it.
+    pub fn line(
+        from: [f32; 2],
+        to: [f32; 2],
+        width: f32,
+        colour: [f32; 4],
+        cap: LineCap) -> Self {
+        let direction = [to[0] - from[0], to[1] - from[1]];
+        let length = (direction[0] * direction[0] + direction[1] * direction[1]).sqrt();
+        let (extension, corner_radius) = match cap {
+            LineCap::Butt => (0.0, 0.0),
+            LineCap::Square => (width, 0.0),
+            LineCap::Round => (width, 0.5)
+        };
+        let centre = [(from[0] + to[0]) / 2.0, (from[1] + to[1]) / 2.0];
+
+        RenderPrimitive::rectangle(centre, [length + extension, width], colour, colour, 0.0, [corner_radius; 4])
+            .rotated(direction[1].atan2(direction[0]))
+    }
+
+    pub fn ellipse(
+        position: [f32; 2],
+        dimensions: [f32; 2],
+        inner_colour: [f32; 4],
+        outer_colour: [f32; 4],
+        stroke_width: f32) -> Self {
+        RenderPrimitive::with_position_size_colours_identification_and_data(
+            position,
+            dimensions,
+            inner_colour,
+            outer_colour,
+            [3, 0],
+            [stroke_width, 0.0, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0]
+        )
+    }
+
+    /// A slice of a circle running clockwise from `start_angle` to `end_angle`, in radians from
+    /// the positive x axis. A whole turn or more draws the full circle.
+    pub fn pie(
+        position: [f32; 2],
+        diameter: f32,
+        start_angle: f32,
+        end_angle: f32,
+        inner_colour: [f32; 4],
+        outer_colour: [f32; 4],
+        stroke_width: f32) -> Self {
+        RenderPrimitive::arc(position, diameter, start_angle, end_angle, 0.0, inner_colour, outer_colour, stroke_width)
+    }
+
+    /// The outer `thickness` pixels of a `pie` slice, with rounded ends. A thickness of zero fills
+    /// the slice instead.
+    #[allow(clippy::too_many_arguments)]
+    pub fn arc(
+        position: [f32; 2],
+        diameter: f32,
+        start_angle: f32,
+        end_angle: f32,
+        thickness: f32,
+        inner_colour: [f32; 4],
+        outer_colour: [f32; 4],
+        stroke_width: f32) -> Self {
+        let sweep = end_angle - start_angle;
+        let half_sweep = if sweep.abs() >= TAU { PI } else { sweep.rem_euclid(TAU) / 2.0 };
+        let (middle_sin, middle_cos) = (start_angle + half_sweep).sin_cos();
+        let (aperture_sin, aperture_cos) = half_sweep.sin_cos();
+
+        // Directions rather than angles, which keep their precision as half floats
+        RenderPrimitive::with_position_size_colours_identification_and_data(
+            position,
+            [diameter, diameter],
+            inner_colour,
+            outer_colour,
+            [4, 0],
+            [stroke_width, thickness.max(0.0), 0.0, 0.0],
+            [middle_cos, middle_sin, aperture_cos, aperture_sin]
+        )
+    }
+
+    pub fn triangle(
+        vertices: [[f32; 2]; 3],
+        inner_colour: [f32; 4],
+        outer_colour: [f32; 4],
+        stroke_width: f32) -> Self {
+        let min = |axis: usize| vertices.iter().map(|vertex| vertex[axis]).fold(f32::INFINITY, f32::min);
+        let max = |axis: usize| vertices.iter().map(|vertex| vertex[axis]).fold(f32::NEG_INFINITY, f32::max);
+        let dimensions = [max(0) - min(0), max(1) - min(1)];
+        let position = [min(0) + dimensions[0] / 2.0, min(1) + dimensions[1] / 2.0];
+
+        // Vertices as fractions of the quad from its centre
+        let relative = |vertex: [f32; 2], axis: usize| {
+            if dimensions[axis] > 0.0 { (vertex[axis] - position[axis]) / dimensions[axis] } else { 0.0 }
+        };
+        RenderPrimitive::with_position_size_colours_identification_and_data(
+            position,
+            dimensions,
+            inner_colour,
+            outer_colour,
+            [5, 0],
+            [stroke_width, relative(vertices[2], 0), relative(vertices[2], 1), 0.0],
+            [relative(vertices[0], 0), relative(vertices[0], 1), relative(vertices[1], 0), relative(vertices[1], 1)]
+        )
+    }
+
+    /// A quadratic Bezier curve stroked `width` pixels wide, or `None` if its quad would be wider
+    /// or taller than `MAX_CURVE_EXTENT`. `stroke::stroke_quadratic` flattens curves one quad
+    /// cannot draw accurately.
+    pub fn quadratic_bezier(
+        from: [f32; 2],
+        control: [f32; 2],
+        to: [f32; 2],
+        width: f32,
+        colour: [f32; 4],
+        start_cap: LineCap,
+        end_cap: LineCap) -> Option<Self> {
+        let points = [from, control, to];
+        let reach = if start_cap == LineCap::Square || end_cap == LineCap::Square {
+            width / 2.0 * std::f32::consts::SQRT_2
+        } else {
+            width / 2.0
+        };
+        let min = |axis: usize| points.iter().map(|point| point[axis]).fold(f32::INFINITY, f32::min) - reach;
+        let max = |axis: usize| points.iter().map(|point| point[axis]).fold(f32::NEG_INFINITY, f32::max) + reach;
+        let dimensions = [max(0) - min(0), max(1) - min(1)];
+        if dimensions[0].max(dimensions[1]) > MAX_CURVE_EXTENT {
+            return None;
+        }
+        let position = [min(0) + dimensions[0] / 2.0, min(1) + dimensions[1] / 2.0];
+
+        // Points as fractions of the quad from its centre, the caps as start + 4 * end
+        let relative = |point: [f32; 2], axis: usize| {
+            if dimensions[axis] > 0.0 { (point[axis] - position[axis]) / dimensions[axis] } else { 0.0 }
+        };
+        Some(RenderPrimitive::with_position_size_colours_identification_and_data(
+            position,
+            dimensions,
+            colour,
+            colour,
+            [7, 0],
+            [width, relative(from, 0), relative(from, 1), start_cap.code() + 4.0 * end_cap.code()],
+            [relative(control, 0), relative(control, 1), relative(to, 0), relative(to, 1)]
+        ))
+    }
+
+    /// One triangle of a filled path, see `path::Path::fill`, or `None` if its quad would be wider
+    /// or taller than `MAX_PATH_EXTENT`. Triangles sharing a quad store their vertices as fractions
+    /// of it from its centre, so the vertices they share come out identical on the GPU. Bits 1, 2
+    /// and 4 of `outline` mark the edges AB, BC and CA on the path's outline, which fade out over
+    /// the pixel inside them.
+    pub fn path_triangle(
+        centre: [f32; 2],
+        dimensions: [f32; 2],
+        vertices: [[f32; 2]; 3],
+        outline: u8,
+        colour: [f32; 4]) -> Option<Self> {
+        if dimensions[0].max(dimensions[1]) > MAX_PATH_EXTENT {
+            return None;
+        }
+        let relative = |vertex: [f32; 2], axis: usize| {
+            if dimensions[axis] > 0.0 { (vertex[axis] - centre[axis]) / dimensions[axis] } else { 0.0 }
+        };
+        let [a, b, c] = vertices.map(|vertex| [relative(vertex, 0), relative(vertex, 1)]);
+        Some(RenderPrimitive::with_position_size_colours_identification_and_data(
+            centre,
+            dimensions,
+            colour,
+            colour,
+            [9, 0],
+            [a[0], a[1], b[0], b[1]],
+            [c[0], c[1], outline as f32, 0.0]
+        ))
+    }
+
+    /// A regular polygon with at least three `sides` and a corner pointing up, its corners on a
+    /// circle `diameter` across.
+    pub fn polygon(
+        position: [f32; 2],
+        diameter: f32,
+        sides: u16,
+        inner_colour: [f32; 4],
+        outer_colour: [f32; 4],
+        stroke_width: f32) -> Self {
+        RenderPrimitive::with_position_size_colours_identification_and_data(
+            position,
+            [diameter, diameter],
+            inner_colour,
+            outer_colour,
+            [6, 0],
+            [stroke_width, sides.max(3) as f32, 0.0, 0.0],
+            [0.0, 0.0, 0.0, 0.0]
+        )
+    }
+
+    /// Grows both dimensions by `increase_in_pixels`, or shrinks them for negative values,
+    /// stopping at zero.
+    pub fn expand_dimensions(&mut self, increase_in_pixels: f32) {
+        self.dimensions[0] = (self.dimensions[0] + increase_in_pixels).max(0.0);
+        self.dimensions[1] = (self.dimensions[1] + increase_in_pixels).max(0.0);
+    }
+
+    /// Like `expand_dimensions`, but `None` if either dimension would go below zero.
+    pub fn checked_expand_dimensions(&self, increase_in_pixels: f32) -> Option<Self> {
+        let dimensions = [self.dimensions[0] + increase_in_pixels, self.dimensions[1] + increase_in_pixels];
+        if dimensions[0] < 0.0 || dimensions[1] < 0.0 || !dimensions[0].is_finite() || !dimensions[1].is_finite() {
+            return None;
+        }
+        Some(Self { dimensions, ..*self })
+    }
+
+    /// Fills the primitive with the gradient at `index` in the renderer's list, see
+    /// `SdfRenderer::set_gradients`, in place of its inner colour. Shapes without a stroke fade
+    /// out in the gradient too. Glyphs keep their flat colour.
+    pub fn with_gradient(self, index: u16) -> Self {
+        match index.checked_add(1) {
+            Some(reference) if self.identification[0] != 2 => Self { identification: [self.identification[0], reference], ..self },
+            _ => self
+        }
+    }
+
+    /// Applies `transform` after any transform the primitive already has.
+    pub fn transformed(self, transform: Transform) -> Self {
+        Self { transform: self.transform.then(&transform), ..self }
+    }
+
+    /// Rotates the primitive about its own centre.
+    pub fn rotated(self, angle: f32) -> Self {
+        let centre = self.transform.apply(self.position);
+        self.rotated_around(angle, centre)
+    }
+
+    pub fn rotated_around(self, angle: f32, pivot: [f32; 2]) -> Self {
+        self.transformed(Transform::rotation(angle).around(pivot))
+    }
+
+    /// The vertex layout the shaders read, see `PackedPrimitive`.
+    pub fn pack(&self) -> PackedPrimitive {
+        let x_axis = self.transform.x_axis;
+        let y_axis = self.transform.y_axis;
+        PackedPrimitive {
+            position: self.transform.apply(self.position),
+            dimensions: self.dimensions,
+            inner_colour: pack_colour(self.inner_colour),
+            outer_colour: pack_colour(self.outer_colour),
+            kind_and_glyph: (self.identification[0] as u32) << 16 | self.identification[1] as u32,
+            extra_data_1: Half4::from_f32(self.extra_data_1),
+            extra_data_2: Half4::from_f32(self.extra_data_2),
+            linear_transform: [x_axis[0], x_axis[1], y_axis[0], y_axis[1]]
+        }
+    }
+}
+
+/// A `RenderPrimitive` as stored in vertex buffers: RGBA8 colours, half float payloads, and the
+/// kind in the high 16 bits of `kind_and_glyph` above the glyph index, or one past the gradient's.
+/// The transform's translation is applied to `position` up front, leaving its 2x2 part for the
+/// quad. 60 bytes: 16 of float position and dimensions, 8 of colours, 4 of kind and glyph, 16 of
+/// half float payloads and 16 of float linear transform.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct PackedPrimitive {
+    pub position: [f32; 2],
+    pub dimensions: [f32; 2],
+    pub inner_colour: [u8; 4],
+    pub outer_colour: [u8; 4],
+    pub kind_and_glyph: u32,
+    pub extra_data_1: Half4,
+    pub extra_data_2: Half4,
+    pub linear_transform: [f32; 4]
+}
+
+impl PackedPrimitive {
+    /// The primitive as the shaders see it, its transform reduced to the 2x2 part around `position`.
+    pub fn unpack(&self) -> RenderPrimitive {
+        let linear = Transform {
+            x_axis: [self.linear_transform[0], self.linear_transform[1]],
+            y_axis: [self.linear_transform[2], self.linear_transform[3]],
+            translation: [0.0, 0.0]
+        };
+        let primitive = RenderPrimitive::with_position_size_colours_identification_and_data(
+            self.position,
+            self.dimensions,
+            unpack_colour(self.inner_colour),
+            unpack_colour(self.outer_colour),
+            [(self.kind_and_glyph >> 16) as u16, self.kind_and_glyph as u16],
+            self.extra_data_1.to_f32(),
+            self.extra_data_2.to_f32()
+        );
+        primitive.transformed(linear.around(self.position))
+    }
+}
+
+implement_vertex!(
+    PackedPrimitive,
+    position normalize(false),
+    dimensions normalize(false),
+    inner_colour normalize(true),
+    outer_colour normalize(true),
+    kind_and_glyph normalize(false),
+    extra_data_1 normalize(false),
+    extra_data_2 normalize(false),
+    linear_transform normalize(false)
+);
+
+fn pack_colour(colour: [f32; 4]) -> [u8; 4] {
+    let channel = |value: f32| (value.clamp(0.0, 1.0) * 255.0).round() as u8;
+    [channel(colour[0]), channel(colour[1]), channel(colour[2]), channel(colour[3])]
+}
+
+fn unpack_colour(colour: [u8; 4]) -> [f32; 4] {
+    [colour[0] as f32 / 255.0, colour[1] as f32 / 255.0, colour[2] as f32 / 255.0, colour[3] as f32 / 255.0]
+}
+
+/// Four IEEE half precision floats, which shaders read as a `vec4`.
+#[derive(Copy, Clone, Debug, Default, PartialEq)]
+pub struct Half4(pub [u16; 4]);
+
+impl Half4 {
+    pub fn from_f32(values: [f32; 4]) -> Self {
+        Half4([f32_to_f16(values[0]), f32_to_f16(values[1]), f32_to_f16(values[2]), f32_to_f16(values[3])])
+    }
+
+    pub fn to_f32(self) -> [f32; 4] {
+        [f16_to_f32(self.0[0]), f16_to_f32(self.0[1]), f16_to_f32(self.0[2]), f16_to_f32(self.0[3])]
+    }
+}
+
+unsafe impl glium::vertex::Attribute for Half4 {
+    fn get_type() -> glium::vertex::AttributeType {
+        glium::vertex::AttributeType::F16F16F16F16
+    }
+}
+
+// Rounds to nearest even, overflowing to infinity and underflowing through subnormals to zero
+fn f32_to_f16(value: f32) -> u16 {
+    let bits = value.to_bits();
+    let sign = ((bits >> 16) & 0x8000) as u16;
+    let exponent = ((bits >> 23) & 0xff) as i32;
+    let mantissa = bits & 0x7f_ffff;
+
+    if exponent == 0xff {
+        let nan = if mantissa != 0 { 0x200 } else { 0 };
+        return sign | 0x7c00 | nan;
+    }
+
+    let exponent = exponent - 127 + 15;
+    if exponent >= 0x1f {
+        return sign | 0x7c00;
+    }
+
+    let (bits, shift) = if exponent <= 0 {
+        if exponent < -10 {
+            return sign;
+        }
+        (mantissa | 0x80_0000, (14 - exponent) as u32)
+    } else {
+        ((exponent as u32) << 23 | mantissa, 13)
+    };
+
+    let half = bits >> shift;
+    let dropped_bits = bits & ((1 << shift) - 1);
+    let halfway = 1 << (shift - 1);
+    let rounded = if dropped_bits > halfway || (dropped_bits == halfway && half & 1 == 1) { half + 1 } else { half };
+    sign | rounded as u16
+}
+
+fn f16_to_f32(half: u16) -> f32 {
+    let sign = if half & 0x8000 != 0 { -1.0 } else { 1.0 };
+    let exponent = ((half >> 10) & 0x1f) as i32;
+    let mantissa = (half & 0x3ff) as f32;
+    sign * match exponent {
+        0 => mantissa * (2.0f32).powi(-24),
+        0x1f if mantissa == 0.0 => f32::INFINITY,
+        0x1f => f32::NAN,
+        _ => (1.0 + mantissa / 1024.0) * (2.0f32).powi(exponent - 15)
+    }
+}
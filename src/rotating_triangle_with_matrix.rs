@@ -1,3 +1,4 @@
+#[allow(clippy::collapsible_match, clippy::needless_borrows_for_generic_args)]
 pub fn run() {
     #[allow(unused_imports)]
     use glium::{glutin, Surface};
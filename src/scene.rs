@@ -0,0 +1,65 @@
+use crate::primitives::RenderPrimitive;
+use crate::transform::Transform;
+
+/// Collects a frame's primitives, applying every transform pushed around them.
+/// Transforms compose on the CPU, the innermost applying first.
+#[derive(Clone, Debug, Default)]
+pub struct Scene {
+    primitives: Vec<RenderPrimitive>,
+    transforms: Vec<Transform>
+}
+
+impl Scene {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Applies `transform` to everything added until the matching `pop_transform`,
+    /// inside any transforms already pushed.
+    pub fn push_transform(&mut self, transform: Transform) {
+        let composed = transform.then(&self.current_transform());
+        self.transforms.push(composed);
+    }
+
+    /// Returns false when there was nothing to pop.
+    pub fn pop_transform(&mut self) -> bool {
+        self.transforms.pop().is_some()
+    }
+
+    /// Runs `build` with `transform` pushed, popping it afterwards.
+    pub fn with_transform<F: FnOnce(&mut Scene)>(&mut self, transform: Transform, build: F) {
+        self.push_transform(transform);
+        build(self);
+        self.pop_transform();
+    }
+
+    /// Everything pushed so far, composed.
+    pub fn current_transform(&self) -> Transform {
+        self.transforms.last().copied().unwrap_or(Transform::IDENTITY)
+    }
+
+    pub fn add(&mut self, primitive: RenderPrimitive) {
+        let transform = self.current_transform();
+        self.primitives.push(primitive.transformed(transform));
+    }
+
+    pub fn extend<I: IntoIterator<Item = RenderPrimitive>>(&mut self, primitives: I) {
+        for primitive in primitives {
+            self.add(primitive);
+        }
+    }
+
+    pub fn primitives(&self) -> &[RenderPrimitive] {
+        &self.primitives
+    }
+
+    pub fn into_primitives(self) -> Vec<RenderPrimitive> {
+        self.primitives
+    }
+
+    /// Removes the primitives and any transforms left pushed, ready for the next frame.
+    pub fn clear(&mut self) {
+        self.primitives.clear();
+        self.transforms.clear();
+    }
+}
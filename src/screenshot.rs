@@ -0,0 +1,36 @@
+use rust_glium::font::FontAtlas;
+use rust_glium::headless::OffscreenRenderer;
+use rust_glium::primitives::RenderPrimitive;
+
+pub fn run() {
+    let white = [1.0, 1.0, 1.0, 1.0];        
+    let black = [0.0, 0.0, 0.0, 1.0];    
+
+    let font_atlas = FontAtlas::segoe_ui();
+    let mut characters = ('!'..='~').cycle();
+
+    let mut primitives = vec!();
+    for y in 0..100 {
+        for x in 0..100 {
+            let index = (y * 100) + x;
+            let position = [x as f32 * 25.0, y as f32 * 25.0];
+            if index % 2 == 0 {
+                primitives.push(RenderPrimitive::circle(position, 20.0, white, white, 0.0));
+            }
+            else if index % 3 == 0 {
+                primitives.push(RenderPrimitive::rectangle(position, [20.0, 20.0], white, white, 0.0, [0.1, 0.3, 0.4, 0.2]));
+            }
+            else {
+                let character = characters.next().unwrap();
+                let glyph_index = font_atlas.font.glyph(character).and_then(|glyph| glyph.glyph_index).unwrap();
+                primitives.push(RenderPrimitive::text(position, [50.0, 50.0], black, glyph_index));
+            }
+        }
+    }
+
+    let mut renderer = OffscreenRenderer::new(1024, 768).unwrap();
+    let render_start = std::time::Instant::now();
+    renderer.render_to_png(&primitives, [0.3, 0.3, 0.5, 1.0], "screenshot.png").unwrap();
+    let render_time = std::time::Instant::now() - render_start;
+    println!("screenshot time: {:?}", render_time);
+}
@@ -0,0 +1,691 @@
+use std::rc::Rc;
+use glium::backend::{Context, Facade};
+use glium::texture::texture2d_array::Texture2dArray;
+use glium::texture::{MipmapsOption, RawImage2d, Texture2d, UncompressedFloatFormat};
+use glium::vertex::VertexBufferSlice;
+use glium::{Program, Surface, VertexBuffer};
+use crate::camera::Camera;
+use crate::font::FontAtlas;
+use crate::gradient::{Gradient, GRADIENT_RAMP_SIZE};
+use crate::primitive_store::PrimitiveStore;
+use crate::primitive_stream::PrimitiveStream;
+use crate::primitives::{PackedPrimitive, RenderPrimitive};
+use crate::transform::Transform;
+
+const INITIAL_VERTEX_CAPACITY: usize = 1024;
+
+const VERTEX_SHADER_SRC: &str = r#"
+    #version 330 core
+
+    uniform vec2 uResolution;
+    uniform mat3 uView;
+    
+    layout (location = 0) in vec2 position;
+    layout (location = 1) in vec2 dimensions;
+    layout (location = 2) in vec4 inner_colour;
+    layout (location = 3) in vec4 outer_colour;
+    layout (location = 4) in uint kind_and_glyph;
+    layout (location = 5) in vec4 extra_data_1;
+    layout (location = 6) in vec4 extra_data_2;
+    layout (location = 7) in vec4 linear_transform;
+    
+    out VS_OUT
+    {
+        vec2 dimensions;
+        vec4 inner_colour;
+        vec4 outer_colour;
+        flat ivec2 identification;
+        vec4 extra_data_1;
+        vec4 extra_data_2;
+        mat2 linear_transform;
+    } vs_out;
+    
+    vec2 toClipSpace(vec2 resolution, vec2 from)
+    {
+        return vec2(
+            from.x / (resolution.x / 2.0) - 1.0,
+            1.0 - (from.y / (resolution.y / 2.0))
+        );
+    }
+    
+    void main()
+    {
+        vec2 viewed = (uView * vec3(position, 1.0)).xy;
+        gl_Position = vec4(toClipSpace(uResolution, viewed), 0.0, 1.0);
+        vs_out.dimensions = dimensions;
+        vs_out.inner_colour = inner_colour;
+        vs_out.outer_colour = outer_colour;
+        vs_out.identification = ivec2(kind_and_glyph >> 16, kind_and_glyph & 0xFFFFu);
+        vs_out.extra_data_1 = extra_data_1;
+        vs_out.extra_data_2 = extra_data_2;
+        vs_out.linear_transform = mat2(uView) * mat2(linear_transform.xy, linear_transform.zw);
+    }
+"#;
+
+const GEOMETRY_SHADER_SRC: &str = r#"
+    #version 330 core
+
+    layout(points) in;
+    layout(triangle_strip, max_vertices = 4) out;
+
+    uniform vec2 uResolution;
+
+    in VS_OUT
+    {
+        vec2 dimensions;
+        vec4 inner_colour;
+        vec4 outer_colour;
+        flat ivec2 identification;
+        vec4 extra_data_1;
+        vec4 extra_data_2;
+        mat2 linear_transform;
+    } gm_in[];
+
+    out GM_OUT 
+    {
+        vec2 dimensions;
+        vec2 texture_coord;
+        vec4 inner_colour;
+        vec4 outer_colour;
+        flat ivec2 identification;
+        vec4 extra_data_1;
+        vec4 extra_data_2;
+    } gm_out;
+
+    // Corners are offsets from the centre in pixels, y down, transformed and viewed before moving to clip space
+    void createVertex(vec2 pos, vec2 corner, float u, float v) {
+        vec2 transformed = gm_in[0].linear_transform * corner;
+        gl_Position = vec4(pos + vec2(transformed.x, -transformed.y) * 2.0 / uResolution, 0.0, 1.0);
+        gm_out.texture_coord = vec2(u, v);
+        gm_out.dimensions = gm_in[0].dimensions;
+        gm_out.inner_colour = gm_in[0].inner_colour;
+        gm_out.outer_colour = gm_in[0].outer_colour;
+        gm_out.identification = gm_in[0].identification;
+        gm_out.extra_data_1 = gm_in[0].extra_data_1;
+        gm_out.extra_data_2 = gm_in[0].extra_data_2;
+        EmitVertex();
+    }
+
+    void main()
+    {
+        vec2 pos = gl_in[0].gl_Position.xy;
+        vec2 half_size = gm_in[0].dimensions / 2.0;
+
+        if(gm_in[0].identification.r == 9)
+        {
+            // Path triangles are emitted as they are. Their vertices are fractions of the path's
+            // quad from its centre, so those they share come out identical and the fill has no cracks.
+            vec2 a = gm_in[0].extra_data_1.xy;
+            vec2 b = gm_in[0].extra_data_1.zw;
+            vec2 c = gm_in[0].extra_data_2.xy;
+            createVertex(pos, a * gm_in[0].dimensions, 0.5 + a.x, 0.5 + a.y);
+            createVertex(pos, b * gm_in[0].dimensions, 0.5 + b.x, 0.5 + b.y);
+            createVertex(pos, c * gm_in[0].dimensions, 0.5 + c.x, 0.5 + c.y);
+            EndPrimitive();
+            return;
+        }
+
+        createVertex(pos, vec2(-half_size.x, half_size.y), 0.0, 1.0);
+        createVertex(pos, vec2(half_size.x, half_size.y), 1.0, 1.0);
+        createVertex(pos, vec2(-half_size.x, -half_size.y), 0.0, 0.0);
+        createVertex(pos, vec2(half_size.x, -half_size.y), 1.0, 0.0);
+
+        EndPrimitive();
+    }
+"#;
+
+const FRAGMENT_SHADER_SRC: &str = r#"
+    #version 330 core
+
+    uniform sampler2DArray font_buffer;
+    uniform sampler2D gradients;
+    uniform int uGradientCount;
+    uniform float uScaleFactor;
+
+    // Neighbouring stroke segments overlap by this much at their joins, in pixels, rather than
+    // leaving gaps where rounding disagrees. The seam sits off the pixel grid so that corners on
+    // it, common with round coordinates, belong to one segment.
+    const float JOIN_OVERLAP = 1.0 / 64.0;
+    // And by this much more per pixel from the join, covering the half float angles of the cuts
+    const float JOIN_ANGLE_OVERLAP = 1.0 / 1024.0;
+
+    in GM_OUT 
+    {
+        vec2 dimensions;
+        vec2 texture_coord;
+        vec4 inner_colour;
+        vec4 outer_colour;
+        flat ivec2 identification;
+        vec4 extra_data_1;
+        vec4 extra_data_2;
+    } fs_in;
+    
+    out vec4 Color;
+    
+    float median(float r, float g, float b)
+    {
+        return max(min(r, g), min(max(r, g), b));
+    }
+    
+    float circle_signed_dist(vec2 position, float radius) 
+    {
+        return length(position) - radius;
+    }
+    
+    // Position from the centre and corner radii in pixels
+    float box_signed_dist(in vec2 position, in vec2 half_size, in vec4 corner_radii)
+    {
+        vec2 quadrant_position = step(vec2(0.0), position);
+        int corner_radius_index = int(quadrant_position.x) + int(quadrant_position.y) * 2;
+        float corner_radius = corner_radii[corner_radius_index];
+    
+        vec2 offset = abs(position) - half_size + corner_radius;
+        return min(max(offset.x, offset.y), 0.0) + length(max(offset, 0.0)) - corner_radius;
+    }
+    
+    // Close to the true distance near the outline, which is all the edges and strokes need
+    float ellipse_signed_dist(vec2 position, vec2 radii)
+    {
+        float scaled = length(position / radii);
+        float gradient = length(position / (radii * radii));
+        return gradient > 0.0 ? scaled * (scaled - 1.0) / gradient : -min(radii.x, radii.y);
+    }
+    
+    // `direction` points through the middle of the slice, and `aperture` holds the cosine and sine
+    // of half its angle. Turned so the slice opens along x, then mirrored onto its lower half.
+    vec2 slice_position(vec2 position, vec2 direction)
+    {
+        return vec2(dot(position, direction), abs(direction.x * position.y - direction.y * position.x));
+    }
+    
+    float pie_signed_dist(vec2 position, vec2 direction, vec2 aperture, float radius)
+    {
+        vec2 p = slice_position(position, direction);
+        float outside_circle = length(p) - radius;
+        float to_side = length(p - aperture * clamp(dot(p, aperture), 0.0, radius));
+        return max(outside_circle, to_side * sign(aperture.x * p.y - aperture.y * p.x));
+    }
+    
+    float arc_signed_dist(vec2 position, vec2 direction, vec2 aperture, float radius, float thickness)
+    {
+        vec2 p = slice_position(position, direction);
+        float centre_radius = radius - 0.5 * thickness;
+        float to_centre_line = aperture.x * p.y > aperture.y * p.x
+            ? length(p - aperture * centre_radius)
+            : abs(length(p) - centre_radius);
+        return to_centre_line - 0.5 * thickness;
+    }
+    
+    float triangle_signed_dist(vec2 position, vec2 a, vec2 b, vec2 c)
+    {
+        vec2 edge_ab = b - a;
+        vec2 edge_bc = c - b;
+        vec2 edge_ca = a - c;
+        vec2 from_a = position - a;
+        vec2 from_b = position - b;
+        vec2 from_c = position - c;
+        vec2 to_ab = from_a - edge_ab * clamp(dot(from_a, edge_ab) / max(dot(edge_ab, edge_ab), 1e-6), 0.0, 1.0);
+        vec2 to_bc = from_b - edge_bc * clamp(dot(from_b, edge_bc) / max(dot(edge_bc, edge_bc), 1e-6), 0.0, 1.0);
+        vec2 to_ca = from_c - edge_ca * clamp(dot(from_c, edge_ca) / max(dot(edge_ca, edge_ca), 1e-6), 0.0, 1.0);
+    
+        // Squared distance to the nearest edge, and which side of the edges the position is on
+        float winding = sign(edge_ab.x * edge_ca.y - edge_ab.y * edge_ca.x);
+        vec2 nearest = min(min(
+            vec2(dot(to_ab, to_ab), winding * (from_a.x * edge_ab.y - from_a.y * edge_ab.x)),
+            vec2(dot(to_bc, to_bc), winding * (from_b.x * edge_bc.y - from_b.y * edge_bc.x))),
+            vec2(dot(to_ca, to_ca), winding * (from_c.x * edge_ca.y - from_c.y * edge_ca.x)));
+        return -sqrt(nearest.x) * sign(nearest.y);
+    }
+    
+    // Folds the position into the sector of the nearest edge, a corner pointing up
+    float polygon_signed_dist(vec2 position, float radius, float sides)
+    {
+        float sector = 6.2831853 / sides;
+        float first_edge = 0.5 * sector - 1.5707963;
+        float edge_angle = first_edge + sector * floor((atan(position.y, position.x) - first_edge) / sector + 0.5);
+        vec2 edge_direction = vec2(cos(edge_angle), sin(edge_angle));
+        vec2 p = vec2(dot(position, edge_direction), edge_direction.x * position.y - edge_direction.y * position.x);
+    
+        float apothem = radius * cos(0.5 * sector);
+        float half_edge = radius * sin(0.5 * sector);
+        return length(p - vec2(apothem, clamp(p.y, -half_edge, half_edge))) * sign(p.x - apothem);
+    }
+    
+    float edge_signed_dist(vec2 position, vec2 from, vec2 to, float winding)
+    {
+        vec2 edge = to - from;
+        return -winding * (edge.x * (position.y - from.y) - edge.y * (position.x - from.x)) / length(edge);
+    }
+    
+    // Inside the edges of a path triangle on the path's outline, marked by the bits of `outline`.
+    // Edges shared with the path's other triangles don't fade.
+    float outline_signed_dist(vec2 position, vec2 a, vec2 b, vec2 c, float outline)
+    {
+        vec2 ab = b - a;
+        vec2 ac = c - a;
+        float winding = sign(ab.x * ac.y - ab.y * ac.x);
+        float dist = -1e6;
+        if(mod(outline, 2.0) >= 1.0) dist = max(dist, edge_signed_dist(position, a, b, winding));
+        if(mod(outline, 4.0) >= 2.0) dist = max(dist, edge_signed_dist(position, b, c, winding));
+        if(outline >= 4.0) dist = max(dist, edge_signed_dist(position, c, a, winding));
+        return dist;
+    }
+    
+    // Past the end of a stroke, with x running outward from the end: butt, square and round caps
+    float cap_signed_dist(vec2 position, float cap, float half_width)
+    {
+        if(cap < 0.5) return max(abs(position.y) - half_width, position.x);
+        if(cap < 1.5) return max(abs(position.y) - half_width, position.x - half_width);
+        return length(vec2(max(position.x, 0.0), position.y)) - half_width;
+    }
+    
+    // Past the end of a stroke segment: the caps, then miter, bevel and round joins. Joins stop
+    // `seam` past the plane with `normal` through the end, where the next segment takes over.
+    float segment_end_signed_dist(vec2 position, vec2 normal, float end, float half_width, float seam)
+    {
+        if(end < 2.5) return cap_signed_dist(position, end, half_width);
+        if(dot(position, normal) > seam + JOIN_ANGLE_OVERLAP * length(position)) return 1e6;
+        if(end > 4.5) return cap_signed_dist(position, 2.0, half_width);
+        if(end > 3.5)
+        {
+            // Across the corner from the outer edge's end, on the side the plane leans away from
+            float side = normal.y > 0.0 ? -1.0 : 1.0;
+            vec2 corner = vec2(0.0, side * half_width);
+            return dot(position - corner, side * vec2(-normal.y, normal.x));
+        }
+        return -1e6;
+    }
+    
+    // Distance to the nearest point of a quadratic Bezier curve and where along it that is,
+    // solving the cubic for the nearest point in closed form
+    vec2 bezier_nearest(vec2 position, vec2 from, vec2 control, vec2 to)
+    {
+        vec2 a = control - from;
+        vec2 b = from - 2.0 * control + to;
+        vec2 d = from - position;
+        if(dot(b, b) < 1e-4)
+        {
+            vec2 chord = to - from;
+            float t = clamp(dot(-d, chord) / max(dot(chord, chord), 1e-6), 0.0, 1.0);
+            return vec2(length(d + chord * t), t);
+        }
+    
+        float kk = 1.0 / dot(b, b);
+        float kx = kk * dot(a, b);
+        float ky = kk * (2.0 * dot(a, a) + dot(d, b)) / 3.0;
+        float kz = kk * dot(d, a);
+        float p = ky - kx * kx;
+        float q = kx * (2.0 * kx * kx - 3.0 * ky) + kz;
+        float h = q * q + 4.0 * p * p * p;
+        if(h >= 0.0)
+        {
+            h = sqrt(h);
+            vec2 x = (vec2(h, -h) - q) / 2.0;
+            vec2 roots = sign(x) * pow(abs(x), vec2(1.0 / 3.0));
+            float t = clamp(roots.x + roots.y - kx, 0.0, 1.0);
+            return vec2(length(d + (2.0 * a + b * t) * t), t);
+        }
+        float z = sqrt(-p);
+        float v = acos(q / (p * z * 2.0)) / 3.0;
+        float m = cos(v);
+        float n = sin(v) * 1.7320508;
+        vec2 t = clamp(vec2(m + m, -n - m) * z - kx, 0.0, 1.0);
+        float first = length(d + (2.0 * a + b * t.x) * t.x);
+        float second = length(d + (2.0 * a + b * t.y) * t.y);
+        return first < second ? vec2(first, t.x) : vec2(second, t.y);
+    }
+    
+    // `position` from the end of a stroke, turned so x runs along `outward`
+    vec2 end_position(vec2 position, vec2 end, vec2 outward)
+    {
+        vec2 from_end = position - end;
+        return vec2(dot(from_end, outward), outward.x * from_end.y - outward.y * from_end.x);
+    }
+    
+    // Past the ends only the caps are drawn, and just inside them the caps fade the edge
+    float with_cap(float dist, vec2 position, float cap, float half_width, bool past_end)
+    {
+        float cap_dist = cap_signed_dist(position, cap, half_width);
+        if(past_end) return cap_dist;
+        return position.x > -half_width && abs(position.y) < half_width ? max(dist, cap_dist) : dist;
+    }
+    
+    vec4 stroke_and_fill(float dist, float smoothness, float stroke_width, vec4 inner_colour, vec4 outer_colour)
+    {
+        float outer = smoothstep(smoothness, -smoothness, dist);
+        float inner = smoothstep(-stroke_width + smoothness, -stroke_width - smoothness, dist);
+        float alpha = smoothstep(0.00, -smoothness, dist);
+        vec4 colour = mix(outer_colour, inner_colour, inner);
+        return vec4(colour.rgb * outer, colour.a * alpha);
+    }
+    
+    // Each gradient is a row of `gradients`: its kind, centre and angle or radius, then its ramp
+    vec4 gradient_colour(int row, vec2 texture_coord, vec2 dimensions)
+    {
+        vec4 shape = texelFetch(gradients, ivec2(0, row), 0);
+        vec2 position = (texture_coord - shape.yz) * dimensions;
+        float offset;
+        if(shape.x < 0.5)
+        {
+            // Corner to corner, as far across the quad as it reaches in that direction
+            vec2 direction = vec2(cos(shape.w), sin(shape.w));
+            offset = dot(position, direction) / dot(abs(direction), dimensions) + 0.5;
+        }
+        else if(shape.x < 1.5)
+        {
+            offset = length((texture_coord - shape.yz) / shape.w);
+        }
+        else
+        {
+            offset = fract((atan(position.y, position.x) - shape.w) / 6.2831853);
+        }
+    
+        float ramp_position = clamp(offset, 0.0, 1.0) * float(textureSize(gradients, 0).x - 2);
+        int index = min(int(ramp_position), textureSize(gradients, 0).x - 3);
+        return mix(
+            texelFetch(gradients, ivec2(1 + index, row), 0),
+            texelFetch(gradients, ivec2(2 + index, row), 0),
+            ramp_position - float(index));
+    }
+    
+    void main()
+    {
+        vec4 inner_colour = fs_in.inner_colour;
+        vec4 outer_colour = fs_in.outer_colour;
+        float stroke_width = fs_in.extra_data_1.r;
+        int gradient = fs_in.identification.g - 1;
+        if(fs_in.identification.r != 2 && gradient >= 0 && gradient < uGradientCount)
+        {
+            inner_colour = gradient_colour(gradient, fs_in.texture_coord, fs_in.dimensions);
+            // Curves, polylines and paths have no separate stroke
+            if(fs_in.identification.r >= 7 || stroke_width <= 0.0) outer_colour = inner_colour;
+        }
+        vec2 pixel_position = (fs_in.texture_coord - 0.5) * fs_in.dimensions;
+        float short_side = min(fs_in.dimensions.x, fs_in.dimensions.y);
+
+        // Distances are in the primitive's own pixels. Measuring how far those move per physical pixel
+        // fades edges over one logical pixel whatever the size, aspect ratio, transform or zoom.
+        float pixel_size = 0.5 * (length(dFdx(pixel_position)) + length(dFdy(pixel_position)));
+        float smoothness = 0.5 * pixel_size * uScaleFactor;
+    
+        float alpha = 0.00;
+        vec3 current_colour;
+        
+        if(fs_in.identification.r == 0) 
+        {
+            float dist = circle_signed_dist(pixel_position, 0.5 * short_side);
+            vec4 shaded = stroke_and_fill(dist, smoothness, stroke_width, inner_colour, outer_colour);
+            alpha = shaded.a;
+            current_colour = shaded.rgb;
+        }
+        
+        if(fs_in.identification.r == 1) 
+        {
+            // Radii are fractions of the shorter side, keeping corners round on long boxes
+            vec4 corner_radii = fs_in.extra_data_2 * short_side;
+            float dist = box_signed_dist(pixel_position, 0.5 * fs_in.dimensions, corner_radii);
+            vec4 shaded = stroke_and_fill(dist, smoothness, stroke_width, inner_colour, outer_colour);
+            alpha = shaded.a;
+            current_colour = shaded.rgb;
+        }
+    
+        if(fs_in.identification.r == 2) 
+        {
+            vec3 sample = texture(font_buffer, vec3(fs_in.texture_coord, fs_in.identification.g)).rgb;
+            float dist = median(sample.r, sample.g, sample.b);
+            // fwidth is per physical pixel, scaled to a logical one to soften edges alike at every scale factor
+            float width = fwidth(dist) * uScaleFactor;
+            alpha = smoothstep(0.5 - width, 0.5 + width, dist) * outer_colour.a;
+            current_colour = outer_colour.rgb;
+        }
+    
+        if(fs_in.identification.r == 3) 
+        {
+            float dist = ellipse_signed_dist(pixel_position, 0.5 * fs_in.dimensions);
+            vec4 shaded = stroke_and_fill(dist, smoothness, stroke_width, inner_colour, outer_colour);
+            alpha = shaded.a;
+            current_colour = shaded.rgb;
+        }
+        
+        if(fs_in.identification.r == 4) 
+        {
+            float radius = 0.5 * short_side;
+            float thickness = fs_in.extra_data_1.g;
+            float dist = thickness > 0.0
+                ? arc_signed_dist(pixel_position, fs_in.extra_data_2.xy, fs_in.extra_data_2.zw, radius, thickness)
+                : pie_signed_dist(pixel_position, fs_in.extra_data_2.xy, fs_in.extra_data_2.zw, radius);
+            vec4 shaded = stroke_and_fill(dist, smoothness, stroke_width, inner_colour, outer_colour);
+            alpha = shaded.a;
+            current_colour = shaded.rgb;
+        }
+        
+        if(fs_in.identification.r == 5) 
+        {
+            // Vertices are stored as fractions of the quad from its centre
+            vec2 a = fs_in.extra_data_2.xy * fs_in.dimensions;
+            vec2 b = fs_in.extra_data_2.zw * fs_in.dimensions;
+            vec2 c = fs_in.extra_data_1.gb * fs_in.dimensions;
+            float dist = triangle_signed_dist(pixel_position, a, b, c);
+            vec4 shaded = stroke_and_fill(dist, smoothness, stroke_width, inner_colour, outer_colour);
+            alpha = shaded.a;
+            current_colour = shaded.rgb;
+        }
+        
+        if(fs_in.identification.r == 6) 
+        {
+            float dist = polygon_signed_dist(pixel_position, 0.5 * short_side, fs_in.extra_data_1.g);
+            vec4 shaded = stroke_and_fill(dist, smoothness, stroke_width, inner_colour, outer_colour);
+            alpha = shaded.a;
+            current_colour = shaded.rgb;
+        }
+        
+        if(fs_in.identification.r == 7) 
+        {
+            // Points are stored as fractions of the quad from its centre, the caps as start + 4 * end
+            float half_width = 0.5 * fs_in.extra_data_1.r;
+            vec2 from = fs_in.extra_data_1.gb * fs_in.dimensions;
+            vec2 control = fs_in.extra_data_2.xy * fs_in.dimensions;
+            vec2 to = fs_in.extra_data_2.zw * fs_in.dimensions;
+            float caps = fs_in.extra_data_1.a;
+    
+            vec2 nearest = bezier_nearest(pixel_position, from, control, to);
+            vec2 start_outward = normalize(from - (control == from ? to : control));
+            vec2 end_outward = normalize(to - (control == to ? from : control));
+            float dist = nearest.x - half_width;
+            dist = with_cap(dist, end_position(pixel_position, from, start_outward), mod(caps, 4.0), half_width, nearest.y <= 0.0);
+            dist = with_cap(dist, end_position(pixel_position, to, end_outward), floor(caps / 4.0), half_width, nearest.y >= 1.0);
+            vec4 shaded = stroke_and_fill(dist, smoothness, 0.0, inner_colour, outer_colour);
+            alpha = shaded.a;
+            current_colour = shaded.rgb;
+        }
+        
+        if(fs_in.identification.r == 8) 
+        {
+            // One segment of a stroked polyline along x, its ends cut at the angles of the joins.
+            // The length comes from the quad rather than a half float, so neighbours agree on
+            // where their join is.
+            float half_width = 0.5 * fs_in.dimensions.y;
+            float half_length = 0.5 * fs_in.dimensions.x - fs_in.extra_data_1.r;
+            float start_angle = fs_in.extra_data_1.g;
+            float end_angle = fs_in.extra_data_1.b;
+            vec2 from_start = vec2(-pixel_position.x - half_length, pixel_position.y);
+            vec2 from_end = vec2(pixel_position.x - half_length, pixel_position.y);
+    
+            float dist = max(abs(pixel_position.y) - half_width, max(
+                segment_end_signed_dist(from_start, vec2(cos(start_angle), sin(start_angle)), fs_in.extra_data_2.x, half_width, -JOIN_OVERLAP),
+                segment_end_signed_dist(from_end, vec2(cos(end_angle), sin(end_angle)), fs_in.extra_data_2.y, half_width, 2.0 * JOIN_OVERLAP)));
+            vec4 shaded = stroke_and_fill(dist, smoothness, 0.0, inner_colour, outer_colour);
+            alpha = shaded.a;
+            current_colour = shaded.rgb;
+        }
+        
+        if(fs_in.identification.r == 9) 
+        {
+            vec2 a = fs_in.extra_data_1.xy * fs_in.dimensions;
+            vec2 b = fs_in.extra_data_1.zw * fs_in.dimensions;
+            vec2 c = fs_in.extra_data_2.xy * fs_in.dimensions;
+            float dist = outline_signed_dist(pixel_position, a, b, c, fs_in.extra_data_2.z);
+            vec4 shaded = stroke_and_fill(dist, smoothness, 0.0, inner_colour, outer_colour);
+            alpha = shaded.a;
+            current_colour = shaded.rgb;
+        }
+    
+        Color = vec4(current_colour, alpha);
+    }
+"#;
+
+pub struct SdfRenderer {
+    context: Rc<Context>,
+    program: Program,
+    font_buffer: Texture2dArray,
+    gradients: Texture2d,
+    gradient_count: i32,
+    vertices: VertexBuffer<PackedPrimitive>,
+    packed: Vec<PackedPrimitive>,
+    camera: Camera,
+    scale_factor: f32
+}
+
+impl SdfRenderer {
+    pub fn new<F: Facade + ?Sized>(facade: &F) -> Self {
+        SdfRenderer::with_font_atlas(facade, &FontAtlas::segoe_ui())
+    }
+
+    pub fn with_font_atlas<F: Facade + ?Sized>(facade: &F, font_atlas: &FontAtlas) -> Self {
+        Self {
+            context: facade.get_context().clone(),
+            program: Program::from_source(facade, VERTEX_SHADER_SRC, FRAGMENT_SHADER_SRC, Some(GEOMETRY_SHADER_SRC)).unwrap(),
+            font_buffer: create_font_buffer(facade, font_atlas),
+            gradients: create_gradient_table(facade, &[]),
+            gradient_count: 0,
+            vertices: VertexBuffer::empty_dynamic(facade, INITIAL_VERTEX_CAPACITY).unwrap(),
+            packed: vec!(),
+            camera: Camera::default(),
+            scale_factor: 1.0
+        }
+    }
+
+    pub fn scale_factor(&self) -> f32 {
+        self.scale_factor
+    }
+
+    /// Primitives and the camera are in logical units, multiplied by the window's scale factor
+    /// to reach physical pixels.
+    pub fn set_scale_factor(&mut self, scale_factor: f64) {
+        self.scale_factor = scale_factor as f32;
+    }
+
+    /// The gradients primitives fill with through `RenderPrimitive::with_gradient`, by their
+    /// index here. Primitives referencing gradients past the end keep their flat colours.
+    pub fn set_gradients(&mut self, gradients: &[Gradient]) {
+        self.gradients = create_gradient_table(&self.context, gradients);
+        self.gradient_count = gradients.len() as i32;
+    }
+
+    pub fn camera(&self) -> Camera {
+        self.camera
+    }
+
+    /// Views every later draw through `camera`.
+    pub fn set_camera(&mut self, camera: Camera) {
+        self.camera = camera;
+    }
+
+    pub fn draw<S: Surface>(&mut self, surface: &mut S, primitives: &[RenderPrimitive]) -> Result<(), glium::DrawError> {
+        if primitives.is_empty() {
+            return Ok(());
+        }
+
+        self.upload(primitives);
+        self.draw_vertices(surface, self.vertices.slice(0..primitives.len()).unwrap(), &self.font_buffer)
+    }
+
+    /// Draws glyphs from another texture array, such as the one a `DynamicFontAtlas` fills in.
+    pub fn draw_with_font_buffer<S: Surface>(&mut self, surface: &mut S, primitives: &[RenderPrimitive], font_buffer: &Texture2dArray) -> Result<(), glium::DrawError> {
+        if primitives.is_empty() {
+            return Ok(());
+        }
+
+        self.upload(primitives);
+        self.draw_vertices(surface, self.vertices.slice(0..primitives.len()).unwrap(), font_buffer)
+    }
+
+    /// Draws the primitives kept in a store, after uploading its changes.
+    pub fn draw_store<S: Surface>(&self, surface: &mut S, store: &mut PrimitiveStore) -> Result<(), glium::DrawError> {
+        store.upload(&self.context);
+        match store.vertices() {
+            Some(vertices) if !store.is_empty() => self.draw_vertices(surface, vertices, &self.font_buffer),
+            _ => Ok(())
+        }
+    }
+
+    /// Streams primitives rebuilt every frame through `stream` and draws them.
+    pub fn draw_stream<S: Surface>(&self, surface: &mut S, stream: &mut PrimitiveStream, primitives: &[RenderPrimitive]) -> Result<(), glium::DrawError> {
+        stream.write(primitives);
+        let result = if primitives.is_empty() {
+            Ok(())
+        } else {
+            self.draw_vertices(surface, stream.vertices(), &self.font_buffer)
+        };
+        stream.end_frame();
+        result
+    }
+
+    fn upload(&mut self, primitives: &[RenderPrimitive]) {
+        self.ensure_capacity(primitives.len());
+        self.packed.clear();
+        self.packed.extend(primitives.iter().map(RenderPrimitive::pack));
+        self.vertices.slice(0..primitives.len()).unwrap().write(&self.packed);
+    }
+
+    fn draw_vertices<S: Surface>(&self, surface: &mut S, vertices: VertexBufferSlice<'_, PackedPrimitive>, font_buffer: &Texture2dArray) -> Result<(), glium::DrawError> {
+        // Taken from the surface on every draw, so resized windows map correctly straight away
+        let (width, height) = surface.get_dimensions();
+        let resolution: [f32; 2] = [width as f32, height as f32];
+
+        let uniforms = uniform! {
+            uResolution: resolution,
+            uView: self.camera.view_transform().then(&Transform::scale([self.scale_factor, self.scale_factor])).to_mat3(),
+            uScaleFactor: self.scale_factor,
+            uGradientCount: self.gradient_count,
+            gradients: self.gradients.sampled()
+                .magnify_filter(glium::uniforms::MagnifySamplerFilter::Nearest)
+                .minify_filter(glium::uniforms::MinifySamplerFilter::Nearest),
+            font_buffer: font_buffer.sampled().magnify_filter(glium::uniforms::MagnifySamplerFilter::Linear)
+        };
+
+        let params = glium::DrawParameters {
+            blend: glium::Blend::alpha_blending(),
+            .. Default::default()
+        };
+
+        let indices = glium::index::NoIndices(glium::index::PrimitiveType::Points);
+        surface.draw(vertices, indices, &self.program, &uniforms, &params)
+    }
+
+    fn ensure_capacity(&mut self, required: usize) {
+        if required <= self.vertices.len() {
+            return;
+        }
+        self.vertices = VertexBuffer::empty_dynamic(&self.context, required.next_power_of_two()).unwrap();
+    }
+}
+
+fn create_font_buffer<F: Facade + ?Sized>(facade: &F, font_atlas: &FontAtlas) -> Texture2dArray {
+    let glyphs = font_atlas.layers
+        .iter()
+        .map(|layer| glium::texture::RawImage2d::from_raw_rgba(layer.as_raw().clone(), layer.dimensions()))
+        .collect();
+
+    Texture2dArray::new(facade, glyphs).unwrap()
+}
+
+// One row per gradient, at least one so there is always a texture to bind
+fn create_gradient_table<F: Facade + ?Sized>(facade: &F, gradients: &[Gradient]) -> Texture2d {
+    let width = GRADIENT_RAMP_SIZE + 1;
+    let mut texels: Vec<f32> = gradients.iter().flat_map(Gradient::texels).flatten().collect();
+    texels.resize(width * gradients.len().max(1) * 4, 0.0);
+
+    let image = RawImage2d::from_raw_rgba(texels, (width as u32, gradients.len().max(1) as u32));
+    Texture2d::with_format(facade, image, UncompressedFloatFormat::F32F32F32F32, MipmapsOption::NoMipmap).unwrap()
+}
@@ -0,0 +1,240 @@
+use crate::primitives::{Half4, LineCap, RenderPrimitive};
+use crate::vector::{cross, dot, length, normalise, subtract};
+
+/// How far flattened curves may stray from the true curve, in pixels.
+pub const DEFAULT_TOLERANCE: f32 = 0.25;
+
+// Flatter curves than this, relative to their chord, lose too much precision in the curve
+// distance solver and are flattened instead
+const MIN_CURVE_BEND: f32 = 0.02;
+
+const MAX_FLATTENED_SEGMENTS: f32 = 1024.0;
+
+// Points closer than this are merged before stroking, as the direction between them is noise
+const MIN_SEGMENT_LENGTH: f32 = 1.0 / 256.0;
+
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum LineJoin {
+    /// Extends the outer edges until they meet, bevelled past `StrokeStyle::miter_limit`.
+    Miter,
+    /// Cuts the corner off between the outer edges.
+    Bevel,
+    /// Rounds the corner with a circle the width of the stroke.
+    Round
+}
+
+impl LineJoin {
+    // After the caps, see `LineCap::code`
+    fn code(self) -> f32 {
+        match self {
+            LineJoin::Miter => 3.0,
+            LineJoin::Bevel => 4.0,
+            LineJoin::Round => 5.0
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct StrokeStyle {
+    pub width: f32,
+    pub colour: [f32; 4],
+    pub cap: LineCap,
+    pub join: LineJoin,
+    /// The longest miter, as a multiple of the width, before it is bevelled instead.
+    pub miter_limit: f32,
+    /// How far flattened curves may stray from the true curve, in pixels.
+    pub tolerance: f32
+}
+
+impl StrokeStyle {
+    /// Butt caps and miter joins with a limit of 4, as in SVG.
+    pub fn new(width: f32, colour: [f32; 4]) -> Self {
+        Self {
+            width,
+            colour,
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+            miter_limit: 4.0,
+            tolerance: DEFAULT_TOLERANCE
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+enum SegmentEnd {
+    Cap(LineCap),
+    /// Meets the neighbouring segment across the plane through the end point with this normal,
+    /// which points into the neighbour.
+    Join(LineJoin, [f32; 2])
+}
+
+/// Strokes the lines between `points` as one primitive per line, each ending where the joins
+/// between them bisect the corners, so translucent strokes don't darken at the joins. Lines
+/// doubling back on themselves fall back to overlapping caps.
+pub fn stroke_polyline(points: &[[f32; 2]], closed: bool, style: &StrokeStyle) -> Vec<RenderPrimitive> {
+    let coincide = |a: &[f32; 2], b: &[f32; 2]| length(subtract(*a, *b)) < MIN_SEGMENT_LENGTH;
+    let mut points: Vec<[f32; 2]> = points.to_vec();
+    points.dedup_by(|a, b| coincide(a, b));
+    if closed && points.len() > 1 && coincide(&points[0], &points[points.len() - 1]) {
+        points.pop();
+    }
+
+    if points.len() < 2 {
+        return points.first().and_then(|&point| lone_point(point, style)).into_iter().collect();
+    }
+
+    let segment_count = if closed && points.len() > 2 { points.len() } else { points.len() - 1 };
+    let closed = segment_count == points.len();
+    let direction = |index: usize| {
+        let from = points[index % points.len()];
+        normalise(subtract(points[(index + 1) % points.len()], from))
+    };
+
+    (0..segment_count).map(|index| {
+        let start = if closed || index > 0 {
+            let previous = direction((index + points.len() - 1) % points.len());
+            join_between(previous, direction(index), style, true)
+        } else {
+            SegmentEnd::Cap(style.cap)
+        };
+        let end = if closed || index + 1 < segment_count {
+            join_between(direction(index), direction(index + 1), style, false)
+        } else {
+            SegmentEnd::Cap(style.cap)
+        };
+        segment(points[index], points[(index + 1) % points.len()], start, end, style)
+    }).collect()
+}
+
+/// Strokes a quadratic Bezier curve as one primitive where it can, flattening it into a polyline
+/// when it is too large or too straight for a single quad to draw accurately.
+pub fn stroke_quadratic(from: [f32; 2], control: [f32; 2], to: [f32; 2], style: &StrokeStyle) -> Vec<RenderPrimitive> {
+    let bend = length(second_difference(from, control, to));
+    let chord = length(subtract(to, from));
+
+    if bend > 0.0 && bend >= MIN_CURVE_BEND * chord {
+        if let Some(curve) = RenderPrimitive::quadratic_bezier(from, control, to, style.width, style.colour, style.cap, style.cap) {
+            return vec!(curve);
+        }
+    }
+    stroke_polyline(&flatten_quadratic(from, control, to, style.tolerance), false, style)
+}
+
+/// Strokes a cubic Bezier curve as one quadratic where that stays within the tolerance,
+/// and flattened into a polyline otherwise.
+pub fn stroke_cubic(from: [f32; 2], control_1: [f32; 2], control_2: [f32; 2], to: [f32; 2], style: &StrokeStyle) -> Vec<RenderPrimitive> {
+    // The furthest the best single quadratic strays from the cubic
+    let third_difference = [
+        to[0] - 3.0 * control_2[0] + 3.0 * control_1[0] - from[0],
+        to[1] - 3.0 * control_2[1] + 3.0 * control_1[1] - from[1]
+    ];
+    let error = 3.0f32.sqrt() / 36.0 * length(third_difference);
+
+    if error <= style.tolerance {
+        let control = [
+            (3.0 * (control_1[0] + control_2[0]) - from[0] - to[0]) / 4.0,
+            (3.0 * (control_1[1] + control_2[1]) - from[1] - to[1]) / 4.0
+        ];
+        stroke_quadratic(from, control, to, style)
+    } else {
+        stroke_polyline(&flatten_cubic(from, control_1, control_2, to, style.tolerance), false, style)
+    }
+}
+
+/// Points along a quadratic Bezier curve, close enough that the lines between them stay
+/// within `tolerance` pixels of it.
+pub fn flatten_quadratic(from: [f32; 2], control: [f32; 2], to: [f32; 2], tolerance: f32) -> Vec<[f32; 2]> {
+    // Lines between points t apart stray at most |B''| t² / 8
+    let bend = length(second_difference(from, control, to));
+    let segments = segment_count(bend / (4.0 * tolerance.max(0.001)));
+
+    (0..=segments).map(|index| {
+        let t = index as f32 / segments as f32;
+        let u = 1.0 - t;
+        [
+            u * u * from[0] + 2.0 * u * t * control[0] + t * t * to[0],
+            u * u * from[1] + 2.0 * u * t * control[1] + t * t * to[1]
+        ]
+    }).collect()
+}
+
+/// Like `flatten_quadratic`, for a cubic Bezier curve.
+pub fn flatten_cubic(from: [f32; 2], control_1: [f32; 2], control_2: [f32; 2], to: [f32; 2], tolerance: f32) -> Vec<[f32; 2]> {
+    let bend = length(second_difference(from, control_1, control_2)).max(length(second_difference(control_1, control_2, to)));
+    let segments = segment_count(3.0 * bend / (4.0 * tolerance.max(0.001)));
+
+    (0..=segments).map(|index| {
+        let t = index as f32 / segments as f32;
+        let u = 1.0 - t;
+        let weights = [u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t];
+        [
+            weights[0] * from[0] + weights[1] * control_1[0] + weights[2] * control_2[0] + weights[3] * to[0],
+            weights[0] * from[1] + weights[1] * control_1[1] + weights[2] * control_2[1] + weights[3] * to[1]
+        ]
+    }).collect()
+}
+
+fn segment_count(squared: f32) -> usize {
+    squared.sqrt().ceil().clamp(1.0, MAX_FLATTENED_SEGMENTS) as usize
+}
+
+// Only the caps of a stroke with no length show
+fn lone_point(point: [f32; 2], style: &StrokeStyle) -> Option<RenderPrimitive> {
+    match style.cap {
+        LineCap::Butt => None,
+        LineCap::Square => Some(RenderPrimitive::rectangle(point, [style.width, style.width], style.colour, style.colour, 0.0, [0.0; 4])),
+        LineCap::Round => Some(RenderPrimitive::circle(point, style.width, style.colour, style.colour, 0.0))
+    }
+}
+
+fn join_between(incoming: [f32; 2], outgoing: [f32; 2], style: &StrokeStyle, at_start: bool) -> SegmentEnd {
+    let bisector = [incoming[0] + outgoing[0], incoming[1] + outgoing[1]];
+    if length(bisector) < 1e-4 {
+        // Doubling straight back has no corner to join
+        return SegmentEnd::Cap(if style.join == LineJoin::Round { LineCap::Round } else { LineCap::Butt });
+    }
+
+    let normal = normalise(bisector);
+    let miter_ratio = 1.0 / dot(normal, incoming);
+    let join = if style.join == LineJoin::Miter && miter_ratio > style.miter_limit { LineJoin::Bevel } else { style.join };
+    SegmentEnd::Join(join, if at_start { [-normal[0], -normal[1]] } else { normal })
+}
+
+fn segment(from: [f32; 2], to: [f32; 2], start: SegmentEnd, end: SegmentEnd, style: &StrokeStyle) -> RenderPrimitive {
+    let offset = subtract(to, from);
+    let segment_length = length(offset);
+    let direction = normalise(offset);
+    let half_width = style.width / 2.0;
+
+    // Each end's code, the angle of its cut from the end's outward direction, and how far past
+    // the end it reaches. Cuts stay within a quarter turn of outward, where half floats are finest.
+    let end_data = |end: SegmentEnd, outward: f32| match end {
+        SegmentEnd::Cap(cap) => (cap.code(), 0.0, if cap == LineCap::Butt { 0.0 } else { half_width }),
+        SegmentEnd::Join(join, normal) => {
+            let local = [outward * dot(normal, direction), cross(direction, normal)];
+            let reach = match join {
+                LineJoin::Miter => half_width * (local[1] / local[0]).abs(),
+                _ => half_width
+            };
+            (join.code(), local[1].atan2(local[0]), reach)
+        }
+    };
+    let (start_code, start_angle, start_reach) = end_data(start, -1.0);
+    let (end_code, end_angle, end_reach) = end_data(end, 1.0);
+    // Rounded to the half float it's stored as, so the shader finds the segment's length exactly
+    let reach = Half4::from_f32([start_reach.max(end_reach), 0.0, 0.0, 0.0]).to_f32()[0];
+
+    RenderPrimitive::with_position_size_colours_identification_and_data(
+        [(from[0] + to[0]) / 2.0, (from[1] + to[1]) / 2.0],
+        [segment_length + 2.0 * reach, style.width],
+        style.colour,
+        style.colour,
+        [8, 0],
+        [reach, start_angle, end_angle, 0.0],
+        [start_code, end_code, 0.0, 0.0]
+    ).rotated(direction[1].atan2(direction[0]))
+}
+
+fn second_difference(a: [f32; 2], b: [f32; 2], c: [f32; 2]) -> [f32; 2] {
+    [a[0] - 2.0 * b[0] + c[0], a[1] - 2.0 * b[1] + c[1]]
+}
@@ -0,0 +1,787 @@
+use std::f32::consts::{FRAC_PI_2, TAU};
+use std::fmt;
+use crate::path::{FillRule, Path};
+use crate::primitives::{LineCap, RenderPrimitive};
+use crate::stroke::{LineJoin, StrokeStyle, DEFAULT_TOLERANCE};
+use crate::transform::Transform;
+use crate::vector::{dot, length};
+
+#[derive(Debug)]
+pub enum SvgError {
+    Io(std::io::Error),
+    Xml(roxmltree::Error),
+    NotSvg,
+    MissingSize
+}
+
+impl fmt::Display for SvgError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SvgError::Io(error) => write!(f, "could not read SVG: {}", error),
+            SvgError::Xml(error) => write!(f, "could not parse SVG: {}", error),
+            SvgError::NotSvg => write!(f, "could not load SVG: the root element is not <svg>"),
+            SvgError::MissingSize => write!(f, "could not size SVG: it has no viewBox, width or height")
+        }
+    }
+}
+
+impl std::error::Error for SvgError {}
+
+impl From<std::io::Error> for SvgError {
+    fn from(error: std::io::Error) -> Self {
+        SvgError::Io(error)
+    }
+}
+
+impl From<roxmltree::Error> for SvgError {
+    fn from(error: roxmltree::Error) -> Self {
+        SvgError::Xml(error)
+    }
+}
+
+/// A vector icon, kept in its own units and turned into primitives at whatever size it is
+/// drawn, so it stays as sharp as the text beside it.
+///
+/// Reads the subset icons use: `path`, `rect`, `circle`, `ellipse`, `line`, `polyline` and
+/// `polygon` inside nested `g` elements, with flat fill and stroke colours, opacities, fill
+/// rules, stroke widths, caps and joins, and transforms, set as attributes or in `style`.
+/// Rects with even corners, circles and ellipses draw as SDF shapes and everything else as
+/// filled and stroked paths. Gradients, `use`, text, clipping, masks and dashes are skipped,
+/// and group opacity applies to each shape in the group separately.
+#[derive(Clone, Debug)]
+pub struct SvgIcon {
+    view_box: [f32; 4],
+    shapes: Vec<Shape>
+}
+
+impl SvgIcon {
+    pub fn open<P: AsRef<std::path::Path>>(path: P) -> Result<Self, SvgError> {
+        let source = std::fs::read_to_string(path)?;
+        SvgIcon::load(&source)
+    }
+
+    pub fn load(source: &str) -> Result<Self, SvgError> {
+        let document = roxmltree::Document::parse(source)?;
+        let root = document.root_element();
+        if root.tag_name().name() != "svg" {
+            return Err(SvgError::NotSvg);
+        }
+
+        let size = [root.attribute("width").and_then(parse_length), root.attribute("height").and_then(parse_length)];
+        let view_box = match root.attribute("viewBox").map(parse_numbers).as_deref() {
+            Some(&[x, y, width, height]) if width > 0.0 && height > 0.0 => [x, y, width, height],
+            _ => match size {
+                [Some(width), Some(height)] if width > 0.0 && height > 0.0 => [0.0, 0.0, width, height],
+                _ => return Err(SvgError::MissingSize)
+            }
+        };
+
+        let mut shapes = vec!();
+        let style = Style::default().inherit(root);
+        collect_shapes(root, &style, &parse_transform(root.attribute("transform").unwrap_or("")), &mut shapes);
+        Ok(Self { view_box, shapes })
+    }
+
+    /// The area of the icon's own units that is drawn, as min x, min y, width and height.
+    pub fn view_box(&self) -> [f32; 4] {
+        self.view_box
+    }
+
+    /// The icon scaled to fit `size`, centred, with its top left corner at `top_left`. Shapes
+    /// painted `currentColor` take `current_colour`, the usual way to tint single colour icons.
+    pub fn primitives(&self, top_left: [f32; 2], size: [f32; 2], current_colour: [f32; 4]) -> Vec<RenderPrimitive> {
+        let [x, y, width, height] = self.view_box;
+        let scale = (size[0] / width).min(size[1] / height);
+        let offset = [
+            top_left[0] + (size[0] - width * scale) / 2.0 - x * scale,
+            top_left[1] + (size[1] - height * scale) / 2.0 - y * scale
+        ];
+        let fit = Transform::scale([scale, scale]).then(&Transform::translation(offset));
+
+        self.shapes.iter()
+            .flat_map(|shape| shape.primitives(&shape.transform.then(&fit), current_colour))
+            .collect()
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum PaintSource {
+    None,
+    CurrentColour,
+    Colour([f32; 4])
+}
+
+// Inherited down the tree, except opacity which builds up from every group
+#[derive(Copy, Clone, Debug)]
+struct Style {
+    fill: PaintSource,
+    fill_opacity: f32,
+    fill_rule: FillRule,
+    stroke: PaintSource,
+    stroke_opacity: f32,
+    stroke_width: f32,
+    cap: LineCap,
+    join: LineJoin,
+    miter_limit: f32,
+    colour: Option<[f32; 4]>,
+    opacity: f32
+}
+
+impl Default for Style {
+    fn default() -> Self {
+        Self {
+            fill: PaintSource::Colour([0.0, 0.0, 0.0, 1.0]),
+            fill_opacity: 1.0,
+            fill_rule: FillRule::NonZero,
+            stroke: PaintSource::None,
+            stroke_opacity: 1.0,
+            stroke_width: 1.0,
+            cap: LineCap::Butt,
+            join: LineJoin::Miter,
+            miter_limit: 4.0,
+            colour: None,
+            opacity: 1.0
+        }
+    }
+}
+
+impl Style {
+    // Values that don't parse leave the inherited ones in place
+    fn inherit(&self, node: roxmltree::Node) -> Self {
+        let mut style = *self;
+        let property = |name: &str| property(node, name);
+
+        if let Some(colour) = property("color").and_then(parse_colour) {
+            style.colour = Some(colour);
+        }
+        if let Some(paint) = property("fill").and_then(parse_paint) {
+            style.fill = paint;
+        }
+        if let Some(paint) = property("stroke").and_then(parse_paint) {
+            style.stroke = paint;
+        }
+        if let Some(opacity) = property("fill-opacity").and_then(parse_opacity) {
+            style.fill_opacity = opacity;
+        }
+        if let Some(opacity) = property("stroke-opacity").and_then(parse_opacity) {
+            style.stroke_opacity = opacity;
+        }
+        if let Some(opacity) = property("opacity").and_then(parse_opacity) {
+            style.opacity *= opacity;
+        }
+        match property("fill-rule") {
+            Some("nonzero") => style.fill_rule = FillRule::NonZero,
+            Some("evenodd") => style.fill_rule = FillRule::EvenOdd,
+            _ => ()
+        }
+        if let Some(width) = property("stroke-width").and_then(parse_length).filter(|width| *width >= 0.0) {
+            style.stroke_width = width;
+        }
+        match property("stroke-linecap") {
+            Some("butt") => style.cap = LineCap::Butt,
+            Some("square") => style.cap = LineCap::Square,
+            Some("round") => style.cap = LineCap::Round,
+            _ => ()
+        }
+        match property("stroke-linejoin") {
+            Some("miter") | Some("miter-clip") | Some("arcs") => style.join = LineJoin::Miter,
+            Some("bevel") => style.join = LineJoin::Bevel,
+            Some("round") => style.join = LineJoin::Round,
+            _ => ()
+        }
+        if let Some(limit) = property("stroke-miterlimit").and_then(|value| value.parse::<f32>().ok()).filter(|limit| *limit >= 1.0) {
+            style.miter_limit = limit;
+        }
+        style
+    }
+
+    fn paint(&self, source: PaintSource, opacity: f32) -> Option<Paint> {
+        let colour = match source {
+            PaintSource::None => return None,
+            PaintSource::CurrentColour => self.colour,
+            PaintSource::Colour(colour) => Some(colour)
+        };
+        Some(Paint { colour, opacity: opacity * self.opacity })
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Paint {
+    /// `None` for the current colour given when drawing.
+    colour: Option<[f32; 4]>,
+    opacity: f32
+}
+
+impl Paint {
+    fn resolve(&self, current_colour: [f32; 4]) -> [f32; 4] {
+        let [red, green, blue, alpha] = self.colour.unwrap_or(current_colour);
+        [red, green, blue, alpha * self.opacity]
+    }
+}
+
+#[derive(Clone, Debug)]
+enum Geometry {
+    /// Top left, size and the radius of every corner.
+    Rect([f32; 2], [f32; 2], f32),
+    /// Centre and radii.
+    Ellipse([f32; 2], [f32; 2]),
+    Path(Path)
+}
+
+#[derive(Copy, Clone, Debug)]
+struct Stroke {
+    paint: Paint,
+    width: f32,
+    cap: LineCap,
+    join: LineJoin,
+    miter_limit: f32
+}
+
+#[derive(Clone, Debug)]
+struct Shape {
+    geometry: Geometry,
+    transform: Transform,
+    fill: Option<(Paint, FillRule)>,
+    stroke: Option<Stroke>
+}
+
+impl Shape {
+    fn primitives(&self, transform: &Transform, current_colour: [f32; 4]) -> Vec<RenderPrimitive> {
+        let fill = self.fill
+            .map(|(paint, fill_rule)| (paint.resolve(current_colour), fill_rule))
+            .filter(|(colour, _)| colour[3] > 0.0);
+        let stroke = self.stroke
+            .map(|stroke| (stroke.paint.resolve(current_colour), stroke))
+            .filter(|(colour, stroke)| colour[3] > 0.0 && stroke.width > 0.0);
+
+        let path = match &self.geometry {
+            Geometry::Path(path) => path,
+            _ => return self.shape_primitives(fill.map(|(colour, _)| colour), stroke)
+                .into_iter()
+                .map(|primitive| primitive.transformed(*transform))
+                .collect()
+        };
+
+        let mut primitives = vec!();
+        if let Some((colour, fill_rule)) = fill {
+            // Fills only need their outline moved, and flatten to the tolerance where they're drawn
+            primitives.extend(path.transformed(transform).fill(fill_rule, colour));
+        }
+        if let Some((colour, stroke)) = stroke {
+            let style = StrokeStyle {
+                cap: stroke.cap,
+                join: stroke.join,
+                miter_limit: stroke.miter_limit,
+                ..StrokeStyle::new(stroke.width, colour)
+            };
+            let scale = length(transform.x_axis).max(length(transform.y_axis));
+            if is_similarity(transform) {
+                primitives.extend(path.transformed(transform).stroke(&StrokeStyle { width: stroke.width * scale, ..style }));
+            } else {
+                // Stretched strokes change width along their length, so they're drawn in the icon's units
+                let style = StrokeStyle { tolerance: DEFAULT_TOLERANCE / scale, ..style };
+                primitives.extend(path.stroke(&style).into_iter().map(|primitive| primitive.transformed(*transform)));
+            }
+        }
+        primitives
+    }
+
+    // SVG strokes straddle the outline, so the SDF shape grows by half the stroke on each side.
+    // Translucent strokes are drawn over a separate fill, which shows through their inner half.
+    fn shape_primitives(&self, fill: Option<[f32; 4]>, stroke: Option<([f32; 4], Stroke)>) -> Vec<RenderPrimitive> {
+        let transparent = |colour: [f32; 4]| [colour[0], colour[1], colour[2], 0.0];
+        match (fill, stroke) {
+            (Some(fill), Some((stroke_colour, stroke))) if stroke_colour[3] >= 1.0 => {
+                vec!(self.shape(fill, stroke_colour, Some(stroke)))
+            },
+            (Some(fill), stroke) => {
+                let mut primitives = vec!(self.shape(fill, fill, None));
+                primitives.extend(stroke.map(|(colour, stroke)| self.shape(transparent(colour), colour, Some(stroke))));
+                primitives
+            },
+            (None, Some((colour, stroke))) => vec!(self.shape(transparent(colour), colour, Some(stroke))),
+            (None, None) => vec!()
+        }
+    }
+
+    fn shape(&self, inner_colour: [f32; 4], outer_colour: [f32; 4], stroke: Option<Stroke>) -> RenderPrimitive {
+        let stroke_width = stroke.map_or(0.0, |stroke| stroke.width);
+        match self.geometry {
+            Geometry::Rect(top_left, size, radius) => {
+                let dimensions = [size[0] + stroke_width, size[1] + stroke_width];
+                let centre = [top_left[0] + size[0] / 2.0, top_left[1] + size[1] / 2.0];
+                // Square corners stay square under miter joins
+                let rounded = radius > 0.0 || stroke.is_some_and(|stroke| stroke.join == LineJoin::Round);
+                let radius = if rounded { (radius + stroke_width / 2.0) / dimensions[0].min(dimensions[1]) } else { 0.0 };
+                RenderPrimitive::rectangle(centre, dimensions, inner_colour, outer_colour, stroke_width, [radius; 4])
+            },
+            Geometry::Ellipse(centre, radii) if radii[0] == radii[1] => {
+                RenderPrimitive::circle(centre, 2.0 * radii[0] + stroke_width, inner_colour, outer_colour, stroke_width)
+            },
+            Geometry::Ellipse(centre, radii) => {
+                let dimensions = [2.0 * radii[0] + stroke_width, 2.0 * radii[1] + stroke_width];
+                RenderPrimitive::ellipse(centre, dimensions, inner_colour, outer_colour, stroke_width)
+            },
+            Geometry::Path(_) => unreachable!("paths are filled and stroked as paths")
+        }
+    }
+}
+
+fn collect_shapes(node: roxmltree::Node, style: &Style, transform: &Transform, shapes: &mut Vec<Shape>) {
+    for child in node.children().filter(|child| child.is_element()) {
+        if property(child, "display") == Some("none") {
+            continue;
+        }
+        let style = style.inherit(child);
+        let transform = parse_transform(child.attribute("transform").unwrap_or("")).then(transform);
+        let number = |name: &str| child.attribute(name).and_then(parse_length).unwrap_or(0.0);
+
+        let geometry = match child.tag_name().name() {
+            "g" | "svg" | "a" => {
+                collect_shapes(child, &style, &transform, shapes);
+                continue;
+            },
+            "path" => Geometry::Path(parse_path_data(child.attribute("d").unwrap_or(""))),
+            "rect" => match rect(number("x"), number("y"), number("width"), number("height"), child.attribute("rx"), child.attribute("ry")) {
+                Some(geometry) => geometry,
+                None => continue
+            },
+            "circle" if number("r") > 0.0 => Geometry::Ellipse([number("cx"), number("cy")], [number("r"); 2]),
+            "ellipse" if number("rx") > 0.0 && number("ry") > 0.0 => {
+                Geometry::Ellipse([number("cx"), number("cy")], [number("rx"), number("ry")])
+            },
+            "line" => {
+                let mut path = Path::new();
+                path.move_to([number("x1"), number("y1")]).line_to([number("x2"), number("y2")]);
+                Geometry::Path(path)
+            },
+            name @ "polyline" | name @ "polygon" => {
+                let mut path = Path::new();
+                for point in parse_numbers(child.attribute("points").unwrap_or("")).chunks_exact(2) {
+                    path.line_to([point[0], point[1]]);
+                }
+                if name == "polygon" {
+                    path.close();
+                }
+                Geometry::Path(path)
+            },
+            _ => continue
+        };
+
+        shapes.push(Shape {
+            geometry,
+            transform,
+            fill: style.paint(style.fill, style.fill_opacity).map(|paint| (paint, style.fill_rule)),
+            stroke: style.paint(style.stroke, style.stroke_opacity).map(|paint| Stroke {
+                paint,
+                width: style.stroke_width,
+                cap: style.cap,
+                join: style.join,
+                miter_limit: style.miter_limit
+            })
+        });
+    }
+}
+
+// An SDF box when its corners are round alike, otherwise a path with elliptical corners
+fn rect(x: f32, y: f32, width: f32, height: f32, rx: Option<&str>, ry: Option<&str>) -> Option<Geometry> {
+    if width <= 0.0 || height <= 0.0 {
+        return None;
+    }
+    let (rx, ry) = match (rx.and_then(parse_length), ry.and_then(parse_length)) {
+        (Some(rx), Some(ry)) => (rx, ry),
+        (Some(radius), None) | (None, Some(radius)) => (radius, radius),
+        (None, None) => (0.0, 0.0)
+    };
+    let (rx, ry) = (rx.clamp(0.0, width / 2.0), ry.clamp(0.0, height / 2.0));
+    if rx == ry {
+        return Some(Geometry::Rect([x, y], [width, height], rx));
+    }
+
+    let mut path = Path::new();
+    let (right, bottom) = (x + width, y + height);
+    path.move_to([x + rx, y]).line_to([right - rx, y]);
+    arc_to(&mut path, [right - rx, y], [rx, ry], 0.0, false, true, [right, y + ry]);
+    path.line_to([right, bottom - ry]);
+    arc_to(&mut path, [right, bottom - ry], [rx, ry], 0.0, false, true, [right - rx, bottom]);
+    path.line_to([x + rx, bottom]);
+    arc_to(&mut path, [x + rx, bottom], [rx, ry], 0.0, false, true, [x, bottom - ry]);
+    path.line_to([x, y + ry]);
+    arc_to(&mut path, [x, y + ry], [rx, ry], 0.0, false, true, [x + rx, y]);
+    path.close();
+    Some(Geometry::Path(path))
+}
+
+// A declaration in the `style` attribute wins over the attribute of the same name
+fn property<'a>(node: roxmltree::Node<'a, '_>, name: &str) -> Option<&'a str> {
+    let declared = node.attribute("style").and_then(|style| {
+        style.rsplit(';')
+            .filter_map(|declaration| declaration.split_once(':'))
+            .find(|(property, _)| property.trim() == name)
+            .map(|(_, value)| value.trim().trim_end_matches("!important").trim())
+    });
+    declared.or_else(|| node.attribute(name).map(str::trim))
+}
+
+fn parse_paint(value: &str) -> Option<PaintSource> {
+    match value {
+        "none" => Some(PaintSource::None),
+        "currentColor" => Some(PaintSource::CurrentColour),
+        // Gradients and patterns aren't read, so use the fallback colour after them
+        value if value.starts_with("url(") => match value.split_once(')').map(|(_, fallback)| fallback.trim()) {
+            Some(fallback) if !fallback.is_empty() => parse_paint(fallback),
+            _ => Some(PaintSource::None)
+        },
+        value => parse_colour(value).map(PaintSource::Colour)
+    }
+}
+
+fn parse_colour(value: &str) -> Option<[f32; 4]> {
+    if let Some(hex) = value.strip_prefix('#') {
+        let digits: Vec<f32> = hex.chars().map(|digit| digit.to_digit(16).map(|digit| digit as f32)).collect::<Option<_>>()?;
+        return match *digits.as_slice() {
+            [r, g, b] => Some([r / 15.0, g / 15.0, b / 15.0, 1.0]),
+            [r, g, b, a] => Some([r / 15.0, g / 15.0, b / 15.0, a / 15.0]),
+            [r1, r0, g1, g0, b1, b0] => Some([(r1 * 16.0 + r0) / 255.0, (g1 * 16.0 + g0) / 255.0, (b1 * 16.0 + b0) / 255.0, 1.0]),
+            [r1, r0, g1, g0, b1, b0, a1, a0] => {
+                Some([(r1 * 16.0 + r0) / 255.0, (g1 * 16.0 + g0) / 255.0, (b1 * 16.0 + b0) / 255.0, (a1 * 16.0 + a0) / 255.0])
+            },
+            _ => None
+        };
+    }
+
+    if let Some(arguments) = value.strip_prefix("rgba(").or_else(|| value.strip_prefix("rgb(")) {
+        let channels: Vec<&str> = arguments.strip_suffix(')')?.split(|c: char| c == ',' || c == '/' || c.is_whitespace()).filter(|channel| !channel.is_empty()).collect();
+        let channel = |value: &str| match value.strip_suffix('%') {
+            Some(percent) => percent.parse::<f32>().ok().map(|percent| percent / 100.0),
+            None => value.parse::<f32>().ok().map(|value| value / 255.0)
+        }.map(|value| value.clamp(0.0, 1.0));
+        return match *channels.as_slice() {
+            [r, g, b] => Some([channel(r)?, channel(g)?, channel(b)?, 1.0]),
+            [r, g, b, a] => Some([channel(r)?, channel(g)?, channel(b)?, parse_opacity(a)?]),
+            _ => None
+        };
+    }
+
+    let [r, g, b] = match value.to_ascii_lowercase().as_str() {
+        "transparent" => return Some([0.0; 4]),
+        "black" => [0, 0, 0],
+        "white" => [255, 255, 255],
+        "red" => [255, 0, 0],
+        "lime" => [0, 255, 0],
+        "green" => [0, 128, 0],
+        "blue" => [0, 0, 255],
+        "yellow" => [255, 255, 0],
+        "cyan" | "aqua" => [0, 255, 255],
+        "magenta" | "fuchsia" => [255, 0, 255],
+        "gray" | "grey" => [128, 128, 128],
+        "silver" => [192, 192, 192],
+        "maroon" => [128, 0, 0],
+        "olive" => [128, 128, 0],
+        "purple" => [128, 0, 128],
+        "teal" => [0, 128, 128],
+        "navy" => [0, 0, 128],
+        "orange" => [255, 165, 0],
+        _ => return None
+    };
+    Some([r as f32 / 255.0, g as f32 / 255.0, b as f32 / 255.0, 1.0])
+}
+
+fn parse_opacity(value: &str) -> Option<f32> {
+    let opacity = match value.strip_suffix('%') {
+        Some(percent) => percent.trim().parse::<f32>().ok()? / 100.0,
+        None => value.parse::<f32>().ok()?
+    };
+    Some(opacity.clamp(0.0, 1.0))
+}
+
+// Plain numbers and pixels, the only units icons use
+fn parse_length(value: &str) -> Option<f32> {
+    let value = value.trim();
+    value.strip_suffix("px").unwrap_or(value).trim().parse().ok()
+}
+
+fn parse_numbers(value: &str) -> Vec<f32> {
+    let mut reader = PathData::new(value);
+    std::iter::from_fn(|| reader.number()).collect()
+}
+
+// A list of transforms applies the last first. A list that doesn't parse is ignored.
+fn parse_transform(value: &str) -> Transform {
+    let mut transform = Transform::IDENTITY;
+    let mut rest = value;
+    loop {
+        rest = rest.trim_start_matches(|c: char| c.is_whitespace() || c == ',');
+        if rest.is_empty() {
+            return transform;
+        }
+        let (name, arguments) = match rest.split_once('(').and_then(|(name, rest)| rest.split_once(')').map(|split| (name, split))) {
+            Some((name, (arguments, remaining))) => {
+                rest = remaining;
+                (name.trim(), parse_numbers(arguments))
+            },
+            None => return Transform::IDENTITY
+        };
+
+        let next = match (name, arguments.as_slice()) {
+            ("matrix", &[a, b, c, d, e, f]) => Transform::from_rows([[a, c, e], [b, d, f]]),
+            ("translate", &[x]) => Transform::translation([x, 0.0]),
+            ("translate", &[x, y]) => Transform::translation([x, y]),
+            ("scale", &[scale]) => Transform::scale([scale, scale]),
+            ("scale", &[x, y]) => Transform::scale([x, y]),
+            ("rotate", &[angle]) => Transform::rotation(angle.to_radians()),
+            ("rotate", &[angle, x, y]) => Transform::rotation(angle.to_radians()).around([x, y]),
+            ("skewX", &[angle]) => Transform::from_rows([[1.0, angle.to_radians().tan(), 0.0], [0.0, 1.0, 0.0]]),
+            ("skewY", &[angle]) => Transform::from_rows([[1.0, 0.0, 0.0], [angle.to_radians().tan(), 1.0, 0.0]]),
+            _ => return Transform::IDENTITY
+        };
+        transform = next.then(&transform);
+    }
+}
+
+// Reads numbers and arc flags out of path data, where separators are optional wherever
+// the next number can't be mistaken for part of the last
+struct PathData<'a> {
+    bytes: &'a [u8],
+    index: usize
+}
+
+impl<'a> PathData<'a> {
+    fn new(data: &'a str) -> Self {
+        Self { bytes: data.as_bytes(), index: 0 }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.bytes.get(self.index).copied()
+    }
+
+    fn skip_separators(&mut self) {
+        while matches!(self.peek(), Some(byte) if byte.is_ascii_whitespace() || byte == b',') {
+            self.index += 1;
+        }
+    }
+
+    fn skip_digits(&mut self) -> usize {
+        let start = self.index;
+        while matches!(self.peek(), Some(byte) if byte.is_ascii_digit()) {
+            self.index += 1;
+        }
+        self.index - start
+    }
+
+    fn command(&mut self) -> Option<u8> {
+        self.skip_separators();
+        let command = self.peek().filter(|byte| byte.is_ascii_alphabetic())?;
+        self.index += 1;
+        Some(command)
+    }
+
+    fn at_number(&mut self) -> bool {
+        self.skip_separators();
+        matches!(self.peek(), Some(byte) if byte.is_ascii_digit() || matches!(byte, b'.' | b'-' | b'+'))
+    }
+
+    fn number(&mut self) -> Option<f32> {
+        self.skip_separators();
+        let start = self.index;
+        if matches!(self.peek(), Some(b'+') | Some(b'-')) {
+            self.index += 1;
+        }
+        let mut digits = self.skip_digits();
+        if self.peek() == Some(b'.') {
+            self.index += 1;
+            digits += self.skip_digits();
+        }
+        if digits == 0 {
+            self.index = start;
+            return None;
+        }
+        if matches!(self.peek(), Some(b'e') | Some(b'E')) {
+            let mantissa_end = self.index;
+            self.index += 1;
+            if matches!(self.peek(), Some(b'+') | Some(b'-')) {
+                self.index += 1;
+            }
+            if self.skip_digits() == 0 {
+                self.index = mantissa_end;
+            }
+        }
+        std::str::from_utf8(&self.bytes[start..self.index]).ok()?.parse().ok()
+    }
+
+    fn point(&mut self) -> Option<[f32; 2]> {
+        Some([self.number()?, self.number()?])
+    }
+
+    fn flag(&mut self) -> Option<bool> {
+        self.skip_separators();
+        let flag = match self.peek()? {
+            b'0' => false,
+            b'1' => true,
+            _ => return None
+        };
+        self.index += 1;
+        Some(flag)
+    }
+}
+
+/// Reads SVG path data into a path, keeping everything up to the first error as SVG does.
+pub fn parse_path_data(data: &str) -> Path {
+    let mut path = Path::new();
+    let mut reader = PathData::new(data);
+    let mut current = [0.0, 0.0];
+    let mut subpath_start = [0.0, 0.0];
+    // The last curve's second control point, reflected by the smooth curve commands that follow
+    let mut last_cubic_control = None;
+    let mut last_quad_control = None;
+    let mut previous_command = None;
+
+    loop {
+        let command = match reader.command() {
+            Some(command) => command,
+            // Coordinates after a move draw lines
+            None if reader.at_number() => match previous_command {
+                Some(b'M') => b'L',
+                Some(b'm') => b'l',
+                Some(b'Z') | Some(b'z') | None => break,
+                Some(command) => command
+            },
+            None => break
+        };
+        previous_command = Some(command);
+
+        let origin = if command.is_ascii_lowercase() { current } else { [0.0, 0.0] };
+        let mut point = || reader.point().map(|point| [origin[0] + point[0], origin[1] + point[1]]);
+        let reflect = |control: Option<[f32; 2]>| control.map_or(current, |control: [f32; 2]| [2.0 * current[0] - control[0], 2.0 * current[1] - control[1]]);
+        let (mut cubic_control, mut quad_control) = (None, None);
+
+        match command.to_ascii_uppercase() {
+            b'M' => {
+                let Some(to) = point() else { break };
+                path.move_to(to);
+                subpath_start = to;
+                current = to;
+            },
+            b'L' => {
+                let Some(to) = point() else { break };
+                path.line_to(to);
+                current = to;
+            },
+            b'H' | b'V' => {
+                let Some(value) = reader.number() else { break };
+                let axis = if command.eq_ignore_ascii_case(&b'H') { 0 } else { 1 };
+                current[axis] = origin[axis] + value;
+                path.line_to(current);
+            },
+            b'C' => {
+                let (Some(control_1), Some(control_2), Some(to)) = (point(), point(), point()) else { break };
+                path.cubic_to(control_1, control_2, to);
+                cubic_control = Some(control_2);
+                current = to;
+            },
+            b'S' => {
+                let control_1 = reflect(last_cubic_control);
+                let (Some(control_2), Some(to)) = (point(), point()) else { break };
+                path.cubic_to(control_1, control_2, to);
+                cubic_control = Some(control_2);
+                current = to;
+            },
+            b'Q' => {
+                let (Some(control), Some(to)) = (point(), point()) else { break };
+                path.quad_to(control, to);
+                quad_control = Some(control);
+                current = to;
+            },
+            b'T' => {
+                let control = reflect(last_quad_control);
+                let Some(to) = point() else { break };
+                path.quad_to(control, to);
+                quad_control = Some(control);
+                current = to;
+            },
+            b'A' => {
+                let (Some(rx), Some(ry), Some(rotation)) = (reader.number(), reader.number(), reader.number()) else { break };
+                let (Some(large_arc), Some(sweep)) = (reader.flag(), reader.flag()) else { break };
+                let Some(to) = reader.point().map(|point| [origin[0] + point[0], origin[1] + point[1]]) else { break };
+                arc_to(&mut path, current, [rx, ry], rotation, large_arc, sweep, to);
+                current = to;
+            },
+            b'Z' => {
+                path.close();
+                current = subpath_start;
+            },
+            _ => break
+        }
+        last_cubic_control = cubic_control;
+        last_quad_control = quad_control;
+    }
+    path
+}
+
+// An elliptical arc from `from` as cubic curves of at most a quarter turn each, following the
+// endpoint to centre conversion in the SVG specification
+fn arc_to(path: &mut Path, from: [f32; 2], radii: [f32; 2], rotation: f32, large_arc: bool, sweep: bool, to: [f32; 2]) {
+    if from == to {
+        return;
+    }
+    let (mut rx, mut ry) = (radii[0].abs(), radii[1].abs());
+    if rx == 0.0 || ry == 0.0 {
+        path.line_to(to);
+        return;
+    }
+
+    let (sin, cos) = rotation.to_radians().sin_cos();
+    let half_chord = [(from[0] - to[0]) / 2.0, (from[1] - to[1]) / 2.0];
+    let x = cos * half_chord[0] + sin * half_chord[1];
+    let y = -sin * half_chord[0] + cos * half_chord[1];
+
+    // Radii too small to reach are scaled up until they just do
+    let reach = (x * x) / (rx * rx) + (y * y) / (ry * ry);
+    if reach > 1.0 {
+        rx *= reach.sqrt();
+        ry *= reach.sqrt();
+    }
+    let numerator = rx * rx * ry * ry - rx * rx * y * y - ry * ry * x * x;
+    let denominator = rx * rx * y * y + ry * ry * x * x;
+    let sign = if large_arc == sweep { -1.0 } else { 1.0 };
+    let coefficient = sign * (numerator / denominator).max(0.0).sqrt();
+    let centre_x = coefficient * rx * y / ry;
+    let centre_y = -coefficient * ry * x / rx;
+
+    let start = [(x - centre_x) / rx, (y - centre_y) / ry];
+    let end = [(-x - centre_x) / rx, (-y - centre_y) / ry];
+    let start_angle = start[1].atan2(start[0]);
+    let mut sweep_angle = (start[0] * end[1] - start[1] * end[0]).atan2(start[0] * end[0] + start[1] * end[1]);
+    if sweep && sweep_angle < 0.0 {
+        sweep_angle += TAU;
+    } else if !sweep && sweep_angle > 0.0 {
+        sweep_angle -= TAU;
+    }
+
+    let centre = [
+        cos * centre_x - sin * centre_y + (from[0] + to[0]) / 2.0,
+        sin * centre_x + cos * centre_y + (from[1] + to[1]) / 2.0
+    ];
+    let map = |unit: [f32; 2]| [
+        centre[0] + cos * rx * unit[0] - sin * ry * unit[1],
+        centre[1] + sin * rx * unit[0] + cos * ry * unit[1]
+    ];
+
+    let segments = (sweep_angle.abs() / FRAC_PI_2 - 1e-3).ceil().max(1.0) as usize;
+    let step = sweep_angle / segments as f32;
+    let handle = 4.0 / 3.0 * (step / 4.0).tan();
+    for segment in 0..segments {
+        let (sin_0, cos_0) = (start_angle + step * segment as f32).sin_cos();
+        let (sin_1, cos_1) = (start_angle + step * (segment + 1) as f32).sin_cos();
+        let control_1 = map([cos_0 - handle * sin_0, sin_0 + handle * cos_0]);
+        let control_2 = map([cos_1 + handle * sin_1, sin_1 - handle * cos_1]);
+        let end = if segment + 1 == segments { to } else { map([cos_1, sin_1]) };
+        path.cubic_to(control_1, control_2, end);
+    }
+}
+
+fn is_similarity(transform: &Transform) -> bool {
+    let [x_axis, y_axis] = [transform.x_axis, transform.y_axis];
+    let scale = length(x_axis).max(length(y_axis));
+    (length(x_axis) - length(y_axis)).abs() <= 1e-4 * scale && dot(x_axis, y_axis).abs() <= 1e-4 * scale * scale
+}
@@ -0,0 +1,44 @@
+use crate::font::Font;
+use crate::primitives::RenderPrimitive;
+
+const TAB_WIDTH_IN_SPACES: f32 = 4.0;
+
+/// Lays out `text` as glyph primitives, `origin` being the top left of the first line
+/// and `size` the font size in pixels. Characters missing from the font are drawn as `?`.
+pub fn layout_text(text: &str, font: &Font, size: f32, origin: [f32; 2], colour: [f32; 4]) -> Vec<RenderPrimitive> {
+    let layer_size = [font.layer_size[0] * size, font.layer_size[1] * size];
+    let space_advance = font.glyph(' ').map_or(0.0, |space| space.advance);
+
+    let mut primitives = vec!();
+    let mut pen = [origin[0], origin[1] + font.ascent * size];
+
+    for character in text.chars() {
+        match character {
+            '\n' => {
+                pen[0] = origin[0];
+                pen[1] += font.line_height * size;
+            },
+            '\r' => (),
+            '\t' => pen[0] += space_advance * TAB_WIDTH_IN_SPACES * size,
+            _ => {
+                let glyph = match font.glyph(character).or_else(|| font.glyph('?')) {
+                    Some(glyph) => glyph,
+                    None => continue
+                };
+
+                if let (Some(glyph_index), Some(plane_bounds)) = (glyph.glyph_index, glyph.plane_bounds) {
+                    // Glyphs sit in the top left of their layer
+                    let centre = [
+                        pen[0] + plane_bounds.left * size + layer_size[0] / 2.0,
+                        pen[1] - plane_bounds.top * size + layer_size[1] / 2.0
+                    ];
+                    primitives.push(RenderPrimitive::text(centre, layer_size, colour, glyph_index));
+                }
+
+                pen[0] += glyph.advance * size;
+            }
+        }
+    }
+
+    primitives
+}
@@ -1,6 +1,7 @@
 use std::io::Cursor;
 use image;
 
+#[allow(clippy::collapsible_match, clippy::approx_constant)]
 pub fn run() {
     #[allow(unused_imports)]
     use glium::{glutin, Surface};
@@ -0,0 +1,104 @@
+/// A 2D affine transform in pixels, mapping `p` to `x_axis * p.x + y_axis * p.y + translation`.
+/// Screen y points down, so positive angles turn clockwise.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Transform {
+    pub x_axis: [f32; 2],
+    pub y_axis: [f32; 2],
+    pub translation: [f32; 2]
+}
+
+impl Default for Transform {
+    fn default() -> Self {
+        Transform::IDENTITY
+    }
+}
+
+impl Transform {
+    pub const IDENTITY: Transform = Transform {
+        x_axis: [1.0, 0.0],
+        y_axis: [0.0, 1.0],
+        translation: [0.0, 0.0]
+    };
+
+    /// From the rows of a 2x3 matrix `[[a, b, tx], [c, d, ty]]`.
+    pub fn from_rows(rows: [[f32; 3]; 2]) -> Self {
+        Self {
+            x_axis: [rows[0][0], rows[1][0]],
+            y_axis: [rows[0][1], rows[1][1]],
+            translation: [rows[0][2], rows[1][2]]
+        }
+    }
+
+    pub fn translation(offset: [f32; 2]) -> Self {
+        Self { translation: offset, ..Transform::IDENTITY }
+    }
+
+    pub fn rotation(angle: f32) -> Self {
+        let (sin, cos) = angle.sin_cos();
+        Self {
+            x_axis: [cos, sin],
+            y_axis: [-sin, cos],
+            translation: [0.0, 0.0]
+        }
+    }
+
+    pub fn scale(scale: [f32; 2]) -> Self {
+        Self {
+            x_axis: [scale[0], 0.0],
+            y_axis: [0.0, scale[1]],
+            translation: [0.0, 0.0]
+        }
+    }
+
+    /// This transform applied around `pivot` rather than the origin.
+    pub fn around(&self, pivot: [f32; 2]) -> Self {
+        Transform::translation([-pivot[0], -pivot[1]]).then(self).then(&Transform::translation(pivot))
+    }
+
+    /// Applies `self`, then `next`.
+    pub fn then(&self, next: &Transform) -> Self {
+        Self {
+            x_axis: next.apply_vector(self.x_axis),
+            y_axis: next.apply_vector(self.y_axis),
+            translation: next.apply(self.translation)
+        }
+    }
+
+    pub fn apply(&self, point: [f32; 2]) -> [f32; 2] {
+        let vector = self.apply_vector(point);
+        [vector[0] + self.translation[0], vector[1] + self.translation[1]]
+    }
+
+    /// Applies the transform without its translation.
+    pub fn apply_vector(&self, vector: [f32; 2]) -> [f32; 2] {
+        [
+            self.x_axis[0] * vector[0] + self.y_axis[0] * vector[1],
+            self.x_axis[1] * vector[0] + self.y_axis[1] * vector[1]
+        ]
+    }
+
+    /// Column major, as a GLSL `mat3` uniform acting on `vec3(point, 1.0)`.
+    pub fn to_mat3(&self) -> [[f32; 3]; 3] {
+        [
+            [self.x_axis[0], self.x_axis[1], 0.0],
+            [self.y_axis[0], self.y_axis[1], 0.0],
+            [self.translation[0], self.translation[1], 1.0]
+        ]
+    }
+
+    /// `None` when the transform flattens everything onto a line or point.
+    pub fn inverse(&self) -> Option<Self> {
+        let determinant = self.x_axis[0] * self.y_axis[1] - self.y_axis[0] * self.x_axis[1];
+        if determinant == 0.0 || !determinant.is_finite() {
+            return None;
+        }
+
+        let linear = Self {
+            x_axis: [self.y_axis[1] / determinant, -self.x_axis[1] / determinant],
+            y_axis: [-self.y_axis[0] / determinant, self.x_axis[0] / determinant],
+            translation: [0.0, 0.0]
+        };
+        let translation = linear.apply_vector(self.translation);
+        Some(Self { translation: [-translation[0], -translation[1]], ..linear })
+    }
+}
@@ -0,0 +1,20 @@
+pub(crate) fn subtract(a: [f32; 2], b: [f32; 2]) -> [f32; 2] {
+    [a[0] - b[0], a[1] - b[1]]
+}
+
+pub(crate) fn dot(a: [f32; 2], b: [f32; 2]) -> f32 {
+    a[0] * b[0] + a[1] * b[1]
+}
+
+pub(crate) fn cross(a: [f32; 2], b: [f32; 2]) -> f32 {
+    a[0] * b[1] - a[1] * b[0]
+}
+
+pub(crate) fn length(vector: [f32; 2]) -> f32 {
+    dot(vector, vector).sqrt()
+}
+
+pub(crate) fn normalise(vector: [f32; 2]) -> [f32; 2] {
+    let length = length(vector);
+    [vector[0] / length, vector[1] / length]
+}
@@ -0,0 +1,55 @@
+use glium::glutin::dpi::PhysicalSize;
+use glium::glutin::event::WindowEvent;
+use glium::Display;
+
+/// A window's framebuffer size and DPI scale factor, kept up to date from its events.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct WindowMetrics {
+    pub framebuffer_size: (u32, u32),
+    pub scale_factor: f64
+}
+
+impl WindowMetrics {
+    pub fn new(framebuffer_size: (u32, u32), scale_factor: f64) -> Self {
+        Self { framebuffer_size, scale_factor }
+    }
+
+    pub fn of(display: &Display) -> Self {
+        Self::new(display.get_framebuffer_dimensions(), display.gl_window().window().scale_factor())
+    }
+
+    /// The framebuffer size as the shaders' `uResolution`.
+    pub fn resolution(&self) -> [f32; 2] {
+        [self.framebuffer_size.0 as f32, self.framebuffer_size.1 as f32]
+    }
+
+    /// The framebuffer size in the logical units primitives are laid out in.
+    pub fn logical_size(&self) -> [f32; 2] {
+        let scale_factor = self.scale_factor as f32;
+        [self.framebuffer_size.0 as f32 / scale_factor, self.framebuffer_size.1 as f32 / scale_factor]
+    }
+
+    /// Records a resize or scale factor change, returning true when the window must be redrawn.
+    pub fn update(&mut self, event: &WindowEvent<'_>) -> bool {
+        let updated = match event {
+            WindowEvent::Resized(size) => Self::new((size.width, size.height), self.scale_factor),
+            WindowEvent::ScaleFactorChanged { scale_factor, new_inner_size } => {
+                Self::new((new_inner_size.width, new_inner_size.height), *scale_factor)
+            },
+            _ => return false
+        };
+        let changed = updated != *self;
+        *self = updated;
+        changed
+    }
+
+    /// `update`, also resizing the display's GL surface, which some platforms leave to the application.
+    pub fn handle_event(&mut self, display: &Display, event: &WindowEvent<'_>) -> bool {
+        if !self.update(event) {
+            return false;
+        }
+        let (width, height) = self.framebuffer_size;
+        display.gl_window().resize(PhysicalSize::new(width, height));
+        true
+    }
+}
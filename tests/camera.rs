@@ -0,0 +1,47 @@
+use std::f32::consts::FRAC_PI_2;
+use rust_glium::camera::Camera;
+
+fn assert_near(actual: [f32; 2], expected: [f32; 2]) {
+    assert!((actual[0] - expected[0]).abs() < 1e-3 && (actual[1] - expected[1]).abs() < 1e-3, "{:?} != {:?}", actual, expected);
+}
+
+#[test]
+fn default_camera_draws_world_at_screen_pixels() {
+    assert_near(Camera::new().world_to_screen([12.0, 34.0]), [12.0, 34.0]);
+}
+
+#[test]
+fn screen_to_world_inverts_world_to_screen() {
+    let camera = Camera { offset: [100.0, -50.0], zoom: 2.5, rotation: 0.7 };
+    let screen = camera.world_to_screen([10.0, 20.0]);
+    assert_near(camera.screen_to_world(screen).unwrap(), [10.0, 20.0]);
+}
+
+#[test]
+fn rotation_turns_the_world_around_the_offset() {
+    let camera = Camera { offset: [10.0, 0.0], zoom: 2.0, rotation: FRAC_PI_2 };
+    assert_near(camera.world_to_screen([11.0, 0.0]), [0.0, 2.0]);
+}
+
+#[test]
+fn panning_follows_the_cursor() {
+    let mut camera = Camera { offset: [0.0, 0.0], zoom: 4.0, rotation: 0.3 };
+    let grabbed = camera.screen_to_world([200.0, 150.0]).unwrap();
+    camera.pan([30.0, -20.0]);
+    assert_near(camera.world_to_screen(grabbed), [230.0, 130.0]);
+}
+
+#[test]
+fn zooming_keeps_the_point_under_the_cursor() {
+    let mut camera = Camera { offset: [40.0, 60.0], zoom: 1.5, rotation: -0.4 };
+    let under_cursor = camera.screen_to_world([320.0, 240.0]).unwrap();
+    camera.zoom_at(3.0, [320.0, 240.0]);
+    assert!((camera.zoom - 4.5).abs() < 1e-5);
+    assert_near(camera.world_to_screen(under_cursor), [320.0, 240.0]);
+}
+
+#[test]
+fn zero_zoom_has_no_world_position() {
+    let camera = Camera { zoom: 0.0, ..Camera::new() };
+    assert_eq!(camera.screen_to_world([1.0, 1.0]), None);
+}
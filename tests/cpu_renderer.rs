@@ -0,0 +1,320 @@
+use std::f32::consts::PI;
+use rust_glium::cpu_renderer::{
+    arc_signed_dist, box_signed_dist, circle_signed_dist, ellipse_signed_dist, median, pie_signed_dist,
+    polygon_signed_dist, smoothstep, triangle_signed_dist, CpuRenderer
+};
+use rust_glium::font::FontAtlas;
+use rust_glium::primitives::{LineCap, RenderPrimitive};
+use rust_glium::stroke::{stroke_polyline, LineJoin, StrokeStyle};
+
+const CLEAR_COLOUR: [f32; 4] = [0.3, 0.3, 0.5, 1.0];
+const CLEAR: [u8; 4] = [77, 77, 128, 255];
+const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const BLACK: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+const RED: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+
+fn pixel(image: &image::RgbaImage, x: u32, y: u32) -> [u8; 4] {
+    image.get_pixel(x, y).0
+}
+
+fn glyph_index(character: char) -> u16 {
+    FontAtlas::segoe_ui().font.glyph(character).and_then(|glyph| glyph.glyph_index).unwrap()
+}
+
+#[test]
+fn median_picks_the_middle_channel() {
+    assert_eq!(median(0.1, 0.5, 0.9), 0.5);
+    assert_eq!(median(0.9, 0.1, 0.5), 0.5);
+    assert_eq!(median(0.5, 0.9, 0.1), 0.5);
+}
+
+#[test]
+fn signed_distances_are_negative_inside() {
+    assert!(circle_signed_dist([0.0, 0.0], 0.5) < 0.0);
+    assert_eq!(circle_signed_dist([0.5, 0.0], 0.5), 0.0);
+    assert!(circle_signed_dist([0.5, 0.5], 0.5) > 0.0);
+
+    assert!(box_signed_dist([0.0, 0.0], [50.0, 20.0], [0.0; 4]) < 0.0);
+    assert!(box_signed_dist([-49.0, -19.0], [50.0, 20.0], [0.0, 0.0, 0.0, 0.0]) < 0.0);
+    assert!(box_signed_dist([-49.0, -19.0], [50.0, 20.0], [10.0, 0.0, 0.0, 0.0]) > 0.0);
+    assert!(box_signed_dist([49.0, 19.0], [50.0, 20.0], [10.0, 0.0, 0.0, 0.0]) < 0.0);
+}
+
+#[test]
+fn smoothstep_handles_reversed_edges() {
+    assert_eq!(smoothstep(0.0, 1.0, 0.5), 0.5);
+    assert_eq!(smoothstep(1.0, 0.0, 0.0), 1.0);
+    assert_eq!(smoothstep(1.0, 0.0, 2.0), 0.0);
+}
+
+#[test]
+fn circle_with_stroke() {
+    let image = CpuRenderer::new().render(&[RenderPrimitive::circle([64.0, 64.0], 100.0, WHITE, BLACK, 8.0)], 128, 128, CLEAR_COLOUR);
+
+    assert_eq!(pixel(&image, 64, 64), [255, 255, 255, 255]);
+    assert_eq!(pixel(&image, 64, 17), [0, 0, 0, 255]);
+    assert_eq!(pixel(&image, 20, 20), CLEAR);
+    assert_eq!(pixel(&image, 64, 10), CLEAR);
+}
+
+#[test]
+fn rectangle_with_mixed_corner_radii() {
+    let primitive = RenderPrimitive::rectangle([64.0, 64.0], [100.0, 80.0], WHITE, BLACK, 6.0, [0.0, 0.1, 0.25, 0.4]);
+    let image = CpuRenderer::new().render(&[primitive], 128, 128, CLEAR_COLOUR);
+
+    assert_eq!(pixel(&image, 64, 64), [255, 255, 255, 255]);
+    assert_eq!(pixel(&image, 15, 25), [0, 0, 0, 255]);
+    assert_eq!(pixel(&image, 112, 102), CLEAR);
+    assert_eq!(pixel(&image, 10, 64), CLEAR);
+}
+
+#[test]
+fn glyph_covers_only_its_outline() {
+    let image = CpuRenderer::new().render(&[RenderPrimitive::text([64.0, 64.0], [96.0, 96.0], BLACK, glyph_index('C'))], 128, 128, CLEAR_COLOUR);
+
+    let black_pixels = image.pixels().filter(|pixel| pixel.0 == [0, 0, 0, 255]).count();
+    assert!(black_pixels > 100);
+    assert_eq!(pixel(&image, 64, 64), CLEAR);
+    assert_eq!(pixel(&image, 120, 120), CLEAR);
+}
+
+#[test]
+fn glyphs_without_an_atlas_stay_transparent() {
+    let mut font_atlas = FontAtlas::segoe_ui();
+    font_atlas.layers.clear();
+    let image = CpuRenderer::with_font_atlas(font_atlas).render(&[RenderPrimitive::text([64.0, 64.0], [96.0, 96.0], BLACK, glyph_index('C'))], 128, 128, CLEAR_COLOUR);
+
+    assert!(image.pixels().all(|pixel| pixel.0 == CLEAR));
+}
+
+#[test]
+fn rectangles_match_distances_from_their_unpacked_input() {
+    let (inner_colour, outer_colour) = ([0.2, 0.6, 0.9, 1.0], [0.7, 0.1, 0.4, 1.0]);
+    let primitive = RenderPrimitive::rectangle([60.5, 66.25], [97.3, 71.9], inner_colour, outer_colour, 7.3, [0.0, 0.1, 0.25, 0.4]);
+    let image = CpuRenderer::new().render(&[primitive], 128, 128, CLEAR_COLOUR);
+
+    // Worked out from the fields as given, so drift through packing shows up as a mismatch
+    let short_side = primitive.dimensions[0].min(primitive.dimensions[1]);
+    let corner_radii = primitive.extra_data_2.map(|radius| radius * short_side);
+    let half_size = [0.5 * primitive.dimensions[0], 0.5 * primitive.dimensions[1]];
+    let stroke_width = primitive.extra_data_1[0];
+    let to_bytes = |colour: [f32; 4]| colour.map(|channel| (channel * 255.0).round() as u8);
+
+    let mut checked = 0;
+    for (x, y, pixel) in image.enumerate_pixels() {
+        let offset = [x as f32 + 0.5 - primitive.position[0], y as f32 + 0.5 - primitive.position[1]];
+        let dist = box_signed_dist(offset, half_size, corner_radii);
+        let expected = if dist > 1.0 {
+            CLEAR
+        } else if dist < -1.0 && dist > -stroke_width + 1.0 {
+            to_bytes(outer_colour)
+        } else if dist < -stroke_width - 1.0 {
+            to_bytes(inner_colour)
+        } else {
+            continue;
+        };
+        assert!(pixel.0.iter().zip(expected.iter()).all(|(&actual, &expected)| (actual as i32 - expected as i32).abs() <= 1),
+            "pixel ({}, {}) at distance {} is {:?}, expected {:?}", x, y, dist, pixel.0, expected);
+        checked += 1;
+    }
+    assert!(checked > 128 * 128 * 9 / 10);
+}
+
+#[test]
+fn later_primitives_draw_over_earlier_ones() {
+    let primitives = [
+        RenderPrimitive::rectangle([64.0, 64.0], [40.0, 40.0], WHITE, WHITE, 0.0, [0.0; 4]),
+        RenderPrimitive::rectangle([64.0, 64.0], [20.0, 20.0], RED, RED, 0.0, [0.0; 4])
+    ];
+    let image = CpuRenderer::new().render(&primitives, 128, 128, CLEAR_COLOUR);
+
+    assert_eq!(pixel(&image, 64, 64), [255, 0, 0, 255]);
+    assert_eq!(pixel(&image, 50, 50), [255, 255, 255, 255]);
+}
+
+#[test]
+fn primitives_can_start_off_screen() {
+    let primitive = RenderPrimitive::rectangle([-10.0, -10.0], [40.0, 40.0], RED, RED, 0.0, [0.0; 4]);
+    let image = CpuRenderer::new().render(&[primitive], 32, 32, CLEAR_COLOUR);
+
+    assert_eq!(pixel(&image, 0, 0), [255, 0, 0, 255]);
+    assert_eq!(pixel(&image, 8, 8), [255, 0, 0, 255]);
+    assert_eq!(pixel(&image, 12, 12), CLEAR);
+}
+
+#[test]
+fn rotated_primitives_turn_about_their_centre() {
+    let bar = RenderPrimitive::rectangle([64.0, 64.0], [80.0, 10.0], RED, RED, 0.0, [0.0; 4]);
+    let upright = CpuRenderer::new().render(&[bar.rotated(std::f32::consts::FRAC_PI_2)], 128, 128, CLEAR_COLOUR);
+
+    assert_eq!(pixel(&upright, 64, 30), [255, 0, 0, 255]);
+    assert_eq!(pixel(&upright, 64, 64), [255, 0, 0, 255]);
+    assert_eq!(pixel(&upright, 30, 64), CLEAR);
+}
+
+#[test]
+fn logical_units_scale_to_physical_pixels() {
+    let physical = CpuRenderer::new().render(&[RenderPrimitive::circle([64.0, 64.0], 100.0, WHITE, BLACK, 8.0)], 128, 128, CLEAR_COLOUR);
+
+    let mut renderer = CpuRenderer::new();
+    renderer.set_scale_factor(2.0);
+    let logical = renderer.render(&[RenderPrimitive::circle([32.0, 32.0], 50.0, WHITE, BLACK, 4.0)], 128, 128, CLEAR_COLOUR);
+
+    assert_eq!(pixel(&logical, 64, 64), [255, 255, 255, 255]);
+    assert_eq!(pixel(&logical, 64, 17), [0, 0, 0, 255]);
+    assert_eq!(pixel(&logical, 20, 20), CLEAR);
+    assert_eq!(pixel(&logical, 64, 10), CLEAR);
+
+    // Edges fade over a logical pixel rather than a physical one, so only pixels within one of the
+    // circle's outline or the stroke's inner edge may differ
+    for (x, y, pixel) in logical.enumerate_pixels() {
+        let radius = ((x as f32 + 0.5 - 64.0).powi(2) + (y as f32 + 0.5 - 64.0).powi(2)).sqrt();
+        if (radius - 50.0).abs() > 1.0 && (radius - 42.0).abs() > 1.0 {
+            assert_eq!(pixel, physical.get_pixel(x, y), "pixel ({}, {})", x, y);
+        }
+    }
+}
+
+fn partially_covered_pixels_across(image: &image::RgbaImage, y: u32) -> usize {
+    (0..image.width()).filter(|&x| ![CLEAR, [255, 0, 0, 255]].contains(&pixel(image, x, y))).count()
+}
+
+#[test]
+fn edges_fade_over_one_pixel_at_any_size() {
+    for size in [8.0, 30.0, 120.0] {
+        let image = CpuRenderer::new().render(&[RenderPrimitive::circle([64.0, 64.0], size, RED, RED, 0.0)], 128, 128, CLEAR_COLOUR);
+        assert!(partially_covered_pixels_across(&image, 64) <= 2, "{} pixel circle edges are blurred", size);
+    }
+}
+
+#[test]
+fn edges_fade_over_one_logical_pixel() {
+    let mut renderer = CpuRenderer::new();
+    renderer.set_scale_factor(4.0);
+    let image = renderer.render(&[RenderPrimitive::rectangle([16.0, 16.0], [20.5, 20.5], RED, RED, 0.0, [0.0; 4])], 128, 128, CLEAR_COLOUR);
+    assert!(partially_covered_pixels_across(&image, 64) >= 4);
+}
+
+#[test]
+fn strokes_keep_their_width_on_long_rectangles() {
+    let image = CpuRenderer::new().render(&[RenderPrimitive::rectangle([64.0, 64.0], [120.0, 40.0], WHITE, BLACK, 6.0, [0.0; 4])], 128, 128, CLEAR_COLOUR);
+
+    // Six pixels of stroke on the short sides as well as the long ones
+    assert_eq!(pixel(&image, 9, 64), [0, 0, 0, 255]);
+    assert_eq!(pixel(&image, 12, 64), [255, 255, 255, 255]);
+    assert_eq!(pixel(&image, 64, 49), [0, 0, 0, 255]);
+    assert_eq!(pixel(&image, 64, 52), [255, 255, 255, 255]);
+}
+
+#[test]
+fn new_shape_distances_are_in_pixels() {
+    assert!((ellipse_signed_dist([60.0, 0.0], [50.0, 20.0]) - 10.0).abs() < 0.01);
+    assert!((ellipse_signed_dist([0.0, -15.0], [50.0, 20.0]) + 5.0).abs() < 0.01);
+
+    // A quarter slice opening downwards, 45 degrees either side
+    let down = [0.0, 1.0];
+    let aperture = [(PI / 4.0).cos(), (PI / 4.0).sin()];
+    assert!(pie_signed_dist([0.0, 10.0], down, aperture, 50.0) < 0.0);
+    assert!(pie_signed_dist([0.0, -10.0], down, aperture, 50.0) > 0.0);
+    assert!((arc_signed_dist([0.0, 45.0], down, aperture, 50.0, 10.0) + 5.0).abs() < 0.01);
+    assert!(arc_signed_dist([0.0, 20.0], down, aperture, 50.0, 10.0) > 0.0);
+
+    let triangle = [[0.0, -20.0], [20.0, 20.0], [-20.0, 20.0]];
+    assert!((triangle_signed_dist([0.0, 25.0], triangle) - 5.0).abs() < 0.01);
+    assert!(triangle_signed_dist([0.0, 0.0], triangle) < 0.0);
+    assert_eq!(triangle_signed_dist([0.0, 25.0], triangle), triangle_signed_dist([0.0, 25.0], [triangle[0], triangle[2], triangle[1]]));
+
+    let apothem = 50.0 * (PI / 6.0).cos();
+    assert!((polygon_signed_dist([0.0, -55.0], 50.0, 6.0) - 5.0).abs() < 0.01);
+    assert!((polygon_signed_dist([apothem + 3.0, 0.0], 50.0, 6.0) - 3.0).abs() < 0.01);
+}
+
+#[test]
+fn lines_run_between_their_end_points() {
+    let butt = RenderPrimitive::line([20.0, 100.0], [100.0, 20.0], 8.0, RED, LineCap::Butt);
+    let round = RenderPrimitive::line([20.0, 100.0], [100.0, 20.0], 8.0, RED, LineCap::Round);
+    let butt_image = CpuRenderer::new().render(&[butt], 128, 128, CLEAR_COLOUR);
+    let round_image = CpuRenderer::new().render(&[round], 128, 128, CLEAR_COLOUR);
+
+    assert_eq!(pixel(&butt_image, 60, 60), [255, 0, 0, 255]);
+    assert_eq!(pixel(&butt_image, 20, 20), CLEAR);
+    assert_eq!(pixel(&butt_image, 102, 18), CLEAR);
+    assert_eq!(pixel(&round_image, 102, 18), [255, 0, 0, 255]);
+}
+
+#[test]
+fn slices_cover_only_their_angles() {
+    // Clockwise from pointing right to pointing down
+    let pie = RenderPrimitive::pie([64.0, 64.0], 100.0, 0.0, PI / 2.0, RED, RED, 0.0);
+    let image = CpuRenderer::new().render(&[pie], 128, 128, CLEAR_COLOUR);
+    assert_eq!(pixel(&image, 90, 90), [255, 0, 0, 255]);
+    assert_eq!(pixel(&image, 38, 90), CLEAR);
+    assert_eq!(pixel(&image, 90, 38), CLEAR);
+
+    let gauge = RenderPrimitive::arc([64.0, 64.0], 100.0, 0.75 * PI, 2.25 * PI, 10.0, RED, RED, 0.0);
+    let image = CpuRenderer::new().render(&[gauge], 128, 128, CLEAR_COLOUR);
+    assert_eq!(pixel(&image, 64, 18), [255, 0, 0, 255]);
+    assert_eq!(pixel(&image, 64, 64), CLEAR);
+    assert_eq!(pixel(&image, 64, 110), CLEAR);
+}
+
+#[test]
+fn ellipses_triangles_and_polygons() {
+    let primitives = [
+        RenderPrimitive::ellipse([32.0, 32.0], [60.0, 20.0], RED, RED, 0.0),
+        RenderPrimitive::triangle([[96.0, 4.0], [124.0, 60.0], [68.0, 60.0]], RED, RED, 0.0),
+        RenderPrimitive::polygon([64.0, 96.0], 60.0, 6, RED, RED, 0.0)
+    ];
+    let image = CpuRenderer::new().render(&primitives, 128, 128, CLEAR_COLOUR);
+
+    assert_eq!(pixel(&image, 58, 32), [255, 0, 0, 255]);
+    assert_eq!(pixel(&image, 32, 45), CLEAR);
+    assert_eq!(pixel(&image, 96, 40), [255, 0, 0, 255]);
+    assert_eq!(pixel(&image, 72, 10), CLEAR);
+    assert_eq!(pixel(&image, 64, 68), [255, 0, 0, 255]);
+    assert_eq!(pixel(&image, 38, 70), CLEAR);
+}
+
+#[test]
+fn curves_follow_their_control_points() {
+    let curve = |start_cap: LineCap, end_cap: LineCap| {
+        let curve = RenderPrimitive::quadratic_bezier([10.0, 100.0], [64.0, -60.0], [118.0, 100.0], 8.0, RED, start_cap, end_cap).unwrap();
+        CpuRenderer::new().render(&[curve], 128, 128, CLEAR_COLOUR)
+    };
+    let butt_image = curve(LineCap::Butt, LineCap::Butt);
+    let round_image = curve(LineCap::Round, LineCap::Round);
+
+    // The curve peaks at y = 20 halfway along
+    assert_eq!(pixel(&butt_image, 64, 20), [255, 0, 0, 255]);
+    assert_eq!(pixel(&butt_image, 64, 30), CLEAR);
+    assert_eq!(pixel(&butt_image, 64, 60), CLEAR);
+    assert_eq!(pixel(&butt_image, 9, 102), CLEAR);
+    assert_eq!(pixel(&butt_image, 119, 102), CLEAR);
+    assert_eq!(pixel(&round_image, 9, 102), [255, 0, 0, 255]);
+    assert_eq!(pixel(&round_image, 119, 102), [255, 0, 0, 255]);
+
+    // Each end takes its own cap
+    let mixed_image = curve(LineCap::Butt, LineCap::Round);
+    assert_eq!(pixel(&mixed_image, 9, 102), CLEAR);
+    assert_eq!(pixel(&mixed_image, 119, 102), [255, 0, 0, 255]);
+}
+
+#[test]
+fn polyline_joins() {
+    let corner = [[20.0, 100.0], [100.0, 100.0], [100.0, 20.0]];
+    let render = |join: LineJoin, colour: [f32; 4]| {
+        let style = StrokeStyle { join, ..StrokeStyle::new(20.0, colour) };
+        CpuRenderer::new().render(&stroke_polyline(&corner, false, &style), 128, 128, CLEAR_COLOUR)
+    };
+
+    assert_eq!(pixel(&render(LineJoin::Miter, RED), 107, 107), [255, 0, 0, 255]);
+    assert_eq!(pixel(&render(LineJoin::Bevel, RED), 107, 107), CLEAR);
+    assert_eq!(pixel(&render(LineJoin::Bevel, RED), 103, 103), [255, 0, 0, 255]);
+    assert_eq!(pixel(&render(LineJoin::Round, RED), 107, 107), CLEAR);
+    assert_eq!(pixel(&render(LineJoin::Round, RED), 105, 105), [255, 0, 0, 255]);
+
+    // The segments meet without overlapping, so translucent strokes are even across the corner
+    let translucent = render(LineJoin::Miter, [1.0, 0.0, 0.0, 0.5]);
+    assert_eq!(pixel(&translucent, 104, 104), pixel(&translucent, 60, 100));
+    assert_eq!(pixel(&translucent, 96, 96), pixel(&translucent, 100, 60));
+}
@@ -0,0 +1,71 @@
+use rust_glium::glyph_cache::{CacheStats, GlyphCache};
+
+#[test]
+fn assigns_layers_in_order_until_full() {
+    let mut cache = GlyphCache::new(2);
+    assert_eq!(cache.insert('a'), Some((0, None)));
+    assert_eq!(cache.insert('b'), Some((1, None)));
+    assert_eq!(cache.insert('a'), Some((0, None)));
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn evicts_the_least_recently_used_glyph() {
+    let mut cache = GlyphCache::new(2);
+    cache.insert('a');
+    cache.insert('b');
+    cache.begin_frame();
+
+    assert_eq!(cache.get('a'), Some(0));
+    cache.begin_frame();
+    assert_eq!(cache.insert('c'), Some((1, Some('b'))));
+    assert_eq!(cache.get('b'), None);
+    assert_eq!(cache.get('c'), Some(1));
+}
+
+#[test]
+fn never_evicts_glyphs_used_this_frame() {
+    let mut cache = GlyphCache::new(2);
+    cache.insert('a');
+    cache.insert('b');
+    assert_eq!(cache.insert('c'), None);
+
+    cache.begin_frame();
+    cache.get('b');
+    assert_eq!(cache.insert('c'), Some((0, Some('a'))));
+    assert_eq!(cache.insert('d'), None);
+}
+
+#[test]
+fn counts_hits_misses_and_evictions() {
+    let mut cache = GlyphCache::new(1);
+    cache.get('a');
+    cache.insert('a');
+    cache.get('a');
+    cache.begin_frame();
+    cache.get('b');
+    cache.insert('b');
+
+    assert_eq!(cache.stats(), CacheStats { hits: 1, misses: 2, evictions: 1, overflows: 0 });
+    cache.reset_stats();
+    assert_eq!(cache.stats(), CacheStats::default());
+}
+
+#[test]
+fn reports_each_overflow_once_a_frame() {
+    let mut cache = GlyphCache::new(1);
+    cache.insert('a');
+    assert!(cache.is_full());
+    assert_eq!(cache.insert('b'), None);
+    assert!(cache.overflowed('b'));
+    cache.overflow('b');
+    cache.overflow('c');
+    assert_eq!(cache.stats().overflows, 2);
+
+    cache.begin_frame();
+    assert!(!cache.is_full());
+    assert!(!cache.overflowed('b'));
+    assert_eq!(cache.insert('b'), Some((0, Some('a'))));
+    assert_eq!(cache.insert('c'), None);
+    assert_eq!(cache.stats().overflows, 3);
+}
@@ -0,0 +1,104 @@
+//! Renders fixed scenes offscreen and compares them to the reference images in `tests/golden`.
+//!
+//! The tests need an OpenGL 3.3 context, so they are ignored by default. On Linux the offscreen
+//! renderer draws through OSMesa, loading `libOSMesa.so` at runtime, so run them against a Mesa
+//! build with OSMesa and llvmpipe, with `LD_LIBRARY_PATH` pointing at it if it isn't installed:
+//!
+//! ```text
+//! LD_LIBRARY_PATH=/path/to/mesa/lib cargo test --test golden -- --ignored
+//! ```
+//!
+//! The references were recorded with llvmpipe, and other drivers may differ by more than
+//! `CHANNEL_TOLERANCE` along edges. A missing or mismatched reference fails the test and
+//! saves the output beside it as `<name>.actual.png`. Check it, then rename it to record it, or set
+//! `UPDATE_GOLDEN=1` to rewrite every reference from the current output.
+
+use std::path::PathBuf;
+use rust_glium::font::FontAtlas;
+use rust_glium::headless::OffscreenRenderer;
+use rust_glium::primitives::RenderPrimitive;
+
+const SCENE_SIZE: u32 = 128;
+const CLEAR_COLOUR: [f32; 4] = [0.3, 0.3, 0.5, 1.0];
+const CHANNEL_TOLERANCE: u8 = 8;
+
+const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+const BLACK: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
+fn golden_path(name: &str, suffix: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("golden").join(format!("{}{}.png", name, suffix))
+}
+
+fn render(primitives: &[RenderPrimitive]) -> image::RgbaImage {
+    let mut renderer = OffscreenRenderer::new(SCENE_SIZE, SCENE_SIZE).expect("golden image tests need an OpenGL 3.3 context");
+    renderer.render(primitives, CLEAR_COLOUR).unwrap()
+}
+
+fn assert_matches_golden(name: &str, actual: &image::RgbaImage) {
+    let reference_path = golden_path(name, "");
+    let actual_path = golden_path(name, ".actual");
+    if std::env::var_os("UPDATE_GOLDEN").is_some() {
+        actual.save(&reference_path).unwrap();
+        return;
+    }
+    if !reference_path.exists() {
+        actual.save(&actual_path).unwrap();
+        panic!("no golden image {:?}, check {:?} and rename it to record it", reference_path, actual_path);
+    }
+
+    let expected = image::open(&reference_path).unwrap().to_rgba8();
+    assert_eq!(expected.dimensions(), actual.dimensions(), "golden image {} has different dimensions", name);
+
+    let mut mismatched_pixels = 0;
+    let diff = image::RgbaImage::from_fn(actual.width(), actual.height(), |x, y| {
+        let expected_pixel = expected.get_pixel(x, y);
+        let actual_pixel = actual.get_pixel(x, y);
+        let difference = (0..4)
+            .map(|channel| (expected_pixel[channel] as i16 - actual_pixel[channel] as i16).unsigned_abs() as u8)
+            .max()
+            .unwrap();
+
+        if difference > CHANNEL_TOLERANCE {
+            mismatched_pixels += 1;
+            image::Rgba([255, 0, 0, 255])
+        } else {
+            let luma = (actual_pixel[0] as u16 + actual_pixel[1] as u16 + actual_pixel[2] as u16) / 6;
+            image::Rgba([luma as u8, luma as u8, luma as u8, 255])
+        }
+    });
+
+    if mismatched_pixels > 0 {
+        let diff_path = golden_path(name, ".diff");
+        actual.save(&actual_path).unwrap();
+        diff.save(&diff_path).unwrap();
+        panic!(
+            "{} pixels of {} differ from {:?} by more than {}, see {:?} and {:?}",
+            mismatched_pixels, name, reference_path, CHANNEL_TOLERANCE, actual_path, diff_path
+        );
+    }
+}
+
+fn glyph_index(character: char) -> u16 {
+    FontAtlas::segoe_ui().font.glyph(character).and_then(|glyph| glyph.glyph_index).unwrap()
+}
+
+#[test]
+#[ignore = "needs an OpenGL 3.3 context"]
+fn circle_with_stroke() {
+    let primitives = [RenderPrimitive::circle([64.0, 64.0], 100.0, WHITE, BLACK, 8.0)];
+    assert_matches_golden("circle_with_stroke", &render(&primitives));
+}
+
+#[test]
+#[ignore = "needs an OpenGL 3.3 context"]
+fn rectangle_with_mixed_corner_radii() {
+    let primitives = [RenderPrimitive::rectangle([64.0, 64.0], [100.0, 80.0], WHITE, BLACK, 6.0, [0.0, 0.1, 0.25, 0.4])];
+    assert_matches_golden("rectangle_with_mixed_corner_radii", &render(&primitives));
+}
+
+#[test]
+#[ignore = "needs an OpenGL 3.3 context"]
+fn glyph() {
+    let primitives = [RenderPrimitive::text([64.0, 64.0], [96.0, 96.0], BLACK, glyph_index('C'))];
+    assert_matches_golden("glyph", &render(&primitives));
+}
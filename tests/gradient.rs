@@ -0,0 +1,86 @@
+use std::f32::consts::FRAC_PI_2;
+use rust_glium::cpu_renderer::CpuRenderer;
+use rust_glium::gradient::Gradient;
+use rust_glium::primitives::RenderPrimitive;
+
+const CLEAR_COLOUR: [f32; 4] = [0.3, 0.3, 0.5, 1.0];
+const RED: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+const GREEN: [f32; 4] = [0.0, 1.0, 0.0, 1.0];
+const BLUE: [f32; 4] = [0.0, 0.0, 1.0, 1.0];
+const BLACK: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
+fn pixel(image: &image::RgbaImage, x: u32, y: u32) -> [u8; 4] {
+    image.get_pixel(x, y).0
+}
+
+fn render(gradients: &[Gradient], primitives: &[RenderPrimitive]) -> image::RgbaImage {
+    let mut renderer = CpuRenderer::new();
+    renderer.set_gradients(gradients);
+    renderer.render(primitives, 128, 128, CLEAR_COLOUR)
+}
+
+#[test]
+fn stops_blend_in_order() {
+    let gradient = Gradient::linear(0.0, &[(1.0, BLUE), (0.0, RED), (0.5, GREEN), (0.5, BLACK)]);
+    assert_eq!(gradient.stops.iter().map(|stop| stop.0).collect::<Vec<_>>(), vec!(0.0, 0.5, 0.5, 1.0));
+
+    assert_eq!(gradient.colour_at(-1.0), RED);
+    assert_eq!(gradient.colour_at(0.25), [0.5, 0.5, 0.0, 1.0]);
+    // Stops at the same offset switch colours sharply
+    assert_eq!(gradient.colour_at(0.5), BLACK);
+    assert_eq!(gradient.colour_at(0.75), [0.0, 0.0, 0.5, 1.0]);
+    assert_eq!(gradient.colour_at(2.0), BLUE);
+
+    // Fading out keeps the colour rather than darkening towards transparent black
+    let fade = Gradient::radial([0.5, 0.5], 0.5, &[(0.0, RED), (1.0, [0.0, 0.0, 0.0, 0.0])]);
+    assert_eq!(fade.colour_at(0.5), [1.0, 0.0, 0.0, 0.5]);
+    assert_eq!(Gradient::linear(0.0, &[]).colour_at(0.5), [0.0; 4]);
+}
+
+#[test]
+fn linear_gradients_span_the_quad() {
+    let gradients = [Gradient::linear(0.0, &[(0.0, RED), (1.0, BLUE)]), Gradient::linear(FRAC_PI_2, &[(0.0, RED), (1.0, BLUE)])];
+    let across = RenderPrimitive::rectangle([64.0, 32.0], [128.0, 64.0], BLACK, BLACK, 0.0, [0.0; 4]).with_gradient(0);
+    let down = RenderPrimitive::rectangle([64.0, 96.0], [128.0, 64.0], BLACK, BLACK, 0.0, [0.0; 4]).with_gradient(1);
+    let image = render(&gradients, &[across, down]);
+
+    assert_eq!(pixel(&image, 2, 32), [250, 0, 5, 255]);
+    assert_eq!(pixel(&image, 63, 32), [128, 0, 127, 255]);
+    assert_eq!(pixel(&image, 125, 32), [5, 0, 250, 255]);
+    assert_eq!(pixel(&image, 64, 66), [245, 0, 10, 255]);
+    assert_eq!(pixel(&image, 64, 125), [10, 0, 245, 255]);
+}
+
+#[test]
+fn radial_and_conic_gradients_centre_on_the_shape() {
+    let gradients = [
+        Gradient::radial([0.5, 0.5], 0.5, &[(0.0, RED), (1.0, BLUE)]),
+        Gradient::conic([0.5, 0.5], -FRAC_PI_2, &[(0.0, RED), (0.25, RED), (0.25, GREEN), (0.5, GREEN), (0.5, BLUE), (1.0, BLUE)])
+    ];
+    let radial = RenderPrimitive::circle([32.0, 32.0], 64.0, BLACK, BLACK, 0.0).with_gradient(0);
+    let conic = RenderPrimitive::circle([96.0, 96.0], 64.0, BLACK, BLACK, 0.0).with_gradient(1);
+    let image = render(&gradients, &[radial, conic]);
+
+    assert_eq!(pixel(&image, 32, 32), [249, 0, 6, 255]);
+    assert_eq!(pixel(&image, 32, 8), [68, 0, 187, 255]);
+    // Clockwise from the top, a quarter turn each of red and green then the rest blue
+    assert_eq!(pixel(&image, 108, 80), [255, 0, 0, 255]);
+    assert_eq!(pixel(&image, 108, 108), [0, 255, 0, 255]);
+    assert_eq!(pixel(&image, 84, 108), [0, 0, 255, 255]);
+    assert_eq!(pixel(&image, 84, 84), [0, 0, 255, 255]);
+}
+
+#[test]
+fn strokes_and_glyphs_keep_their_colours() {
+    let gradients = [Gradient::linear(0.0, &[(0.0, GREEN), (1.0, GREEN)])];
+    let stroked = RenderPrimitive::circle([32.0, 32.0], 64.0, BLACK, RED, 8.0).with_gradient(0);
+    let missing = RenderPrimitive::circle([96.0, 32.0], 64.0, BLUE, BLUE, 0.0).with_gradient(1);
+    let image = render(&gradients, &[stroked, missing]);
+
+    assert_eq!(pixel(&image, 32, 32), [0, 255, 0, 255]);
+    assert_eq!(pixel(&image, 32, 4), [255, 0, 0, 255]);
+    assert_eq!(pixel(&image, 96, 32), [0, 0, 255, 255]);
+
+    let glyph = RenderPrimitive::text([64.0, 64.0], [64.0, 64.0], RED, 7);
+    assert_eq!(glyph.with_gradient(0), glyph);
+}
@@ -0,0 +1,92 @@
+use rust_glium::cpu_renderer::CpuRenderer;
+use rust_glium::font::{FontAtlas, FontError};
+use rust_glium::msdf::MsdfGenerator;
+use rust_glium::text::layout_text;
+
+// A Basic Latin subset of Source Sans Pro, see fonts/OFL.txt
+const FONT: &[u8] = include_bytes!("fonts/SourceSansPro-Regular-Tiny.ttf");
+const CLEAR_COLOUR: [f32; 4] = [0.3, 0.3, 0.5, 1.0];
+const CLEAR: [u8; 4] = [77, 77, 128, 255];
+const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+fn generator() -> MsdfGenerator {
+    MsdfGenerator::new(FONT.to_vec(), 32.0).unwrap()
+}
+
+#[test]
+fn invalid_font_data_is_an_error() {
+    assert!(matches!(MsdfGenerator::new(vec![0; 64], 32.0), Err(FontError::Face(_))));
+}
+
+#[test]
+fn pixels_per_em_must_be_positive() {
+    for pixels_per_em in [0.0, -32.0, f32::NAN, f32::INFINITY] {
+        assert!(matches!(MsdfGenerator::new(FONT.to_vec(), pixels_per_em), Err(FontError::PixelsPerEm(_))));
+    }
+}
+
+#[test]
+fn metrics_come_from_the_font() {
+    let font = generator().empty_font();
+    assert!(font.ascent > 0.5 && font.ascent < 1.5);
+    assert!(font.descent < 0.0);
+    assert!(font.line_height >= font.ascent - font.descent);
+    assert!(font.glyph('A').is_none());
+}
+
+#[test]
+fn spaces_have_an_advance_but_no_layer() {
+    let generator = generator();
+    let atlas = generator.atlas(" A".chars());
+    let space = atlas.font.glyph(' ').unwrap();
+    assert!(space.advance > 0.0);
+    assert_eq!(space.glyph_index, None);
+    assert_eq!(atlas.font.glyph('A').unwrap().glyph_index, Some(0));
+    assert_eq!(atlas.layers.len(), 1);
+    assert_eq!(atlas.layers[0].dimensions(), generator.layer_dimensions());
+}
+
+#[test]
+fn characters_missing_from_the_font_are_not_generated() {
+    assert!(generator().generate('\u{10FFFD}').is_none());
+}
+
+#[test]
+fn generated_glyphs_render_their_outline() {
+    let atlas = generator().atlas("O".chars());
+    let primitives = layout_text("O", &atlas.font, 64.0, [16.0, 16.0], WHITE);
+    let image = CpuRenderer::with_font_atlas(atlas).render(&primitives, 128, 128, CLEAR_COLOUR);
+
+    let white: Vec<(u32, u32)> = image
+        .enumerate_pixels()
+        .filter(|(_, _, pixel)| pixel.0 == [255, 255, 255, 255])
+        .map(|(x, y, _)| (x, y))
+        .collect();
+    assert!(white.len() > 200);
+
+    // The counter of the O stays clear
+    let (min_x, max_x) = (white.iter().map(|p| p.0).min().unwrap(), white.iter().map(|p| p.0).max().unwrap());
+    let (min_y, max_y) = (white.iter().map(|p| p.1).min().unwrap(), white.iter().map(|p| p.1).max().unwrap());
+    assert_eq!(image.get_pixel((min_x + max_x) / 2, (min_y + max_y) / 2).0, CLEAR);
+    assert!(max_x - min_x > 30 && max_y - min_y > 30);
+}
+
+#[test]
+fn atlas_sheets_load_back_with_the_same_glyphs() {
+    let generator = generator();
+    let (metadata, atlas_image) = generator.atlas_sheet(" AOg".chars());
+    let loaded = FontAtlas::load(&metadata, &atlas_image).unwrap();
+    let generated = generator.atlas(" AOg".chars());
+
+    assert_eq!((loaded.font.ascent, loaded.font.descent, loaded.font.line_height), (generated.font.ascent, generated.font.descent, generated.font.line_height));
+    for character in " AOg".chars() {
+        assert_eq!(loaded.font.glyph(character), generated.font.glyph(character));
+    }
+
+    // Layers are cut down to the largest glyph rather than the font's bounding box
+    assert_eq!(loaded.layers.len(), 3);
+    for (loaded_layer, generated_layer) in loaded.layers.iter().zip(&generated.layers) {
+        let (width, height) = loaded_layer.dimensions();
+        assert_eq!(loaded_layer, &image::imageops::crop_imm(generated_layer, 0, 0, width, height).to_image());
+    }
+}
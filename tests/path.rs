@@ -0,0 +1,200 @@
+use rust_glium::cpu_renderer::CpuRenderer;
+use rust_glium::path::{tessellate, FillRule, Path, PathSegment};
+use rust_glium::primitives::{Half4, RenderPrimitive, MAX_PATH_EXTENT};
+use rust_glium::transform::Transform;
+
+const CLEAR_COLOUR: [f32; 4] = [0.3, 0.3, 0.5, 1.0];
+const RED: [f32; 4] = [1.0, 0.0, 0.0, 1.0];
+const BLUE: [f32; 4] = [0.0, 0.0, 1.0, 1.0];
+
+fn square(path: &mut Path, centre: [f32; 2], size: f32, clockwise: bool) {
+    let half = size / 2.0;
+    let corners = [[-half, -half], [half, -half], [half, half], [-half, half]];
+    let order: [usize; 4] = if clockwise { [0, 1, 2, 3] } else { [0, 3, 2, 1] };
+    path.move_to([centre[0] + corners[order[0]][0], centre[1] + corners[order[0]][1]]);
+    for &index in &order[1..] {
+        path.line_to([centre[0] + corners[index][0], centre[1] + corners[index][1]]);
+    }
+    path.close();
+}
+
+fn filled_area(path: &Path, fill_rule: FillRule) -> f32 {
+    let rings: Vec<Vec<[f32; 2]>> = path.flatten(0.1).into_iter().map(|(points, _)| points).collect();
+    tessellate(&rings, fill_rule).iter().map(|([a, b, c], _)| {
+        ((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])).abs() / 2.0
+    }).sum()
+}
+
+#[test]
+fn drawing_without_a_subpath_starts_one() {
+    let mut path = Path::new();
+    path.close().line_to([1.0, 2.0]).quad_to([3.0, 4.0], [5.0, 6.0]).close();
+    assert_eq!(path.segments(), &[
+        PathSegment::MoveTo([1.0, 2.0]),
+        PathSegment::QuadTo([3.0, 4.0], [5.0, 6.0]),
+        PathSegment::Close
+    ]);
+}
+
+#[test]
+fn subpaths_flatten_separately() {
+    let mut path = Path::new();
+    path.move_to([0.0, 0.0]).line_to([10.0, 0.0]).line_to([10.0, 10.0]).close().line_to([0.0, 10.0]);
+    path.move_to([20.0, 0.0]).cubic_to([20.0, 10.0], [30.0, 10.0], [30.0, 0.0]);
+
+    let subpaths = path.flatten(0.25);
+    assert_eq!(subpaths.len(), 3);
+    assert_eq!(subpaths[0], (vec!([0.0, 0.0], [10.0, 0.0], [10.0, 10.0]), true));
+    // Drawing on after a close starts again from the closed subpath's start
+    assert_eq!(subpaths[1], (vec!([0.0, 0.0], [0.0, 10.0]), false));
+    assert!(subpaths[2].0.len() > 3);
+    assert_eq!(subpaths[2].0.last(), Some(&[30.0, 0.0]));
+}
+
+#[test]
+fn fill_rules_decide_which_holes_stay_open() {
+    let mut same_way = Path::new();
+    square(&mut same_way, [50.0, 50.0], 100.0, true);
+    square(&mut same_way, [50.0, 50.0], 50.0, true);
+    assert_eq!(filled_area(&same_way, FillRule::NonZero), 10000.0);
+    assert_eq!(filled_area(&same_way, FillRule::EvenOdd), 7500.0);
+
+    let mut opposite_ways = Path::new();
+    square(&mut opposite_ways, [50.0, 50.0], 100.0, true);
+    square(&mut opposite_ways, [50.0, 50.0], 50.0, false);
+    assert_eq!(filled_area(&opposite_ways, FillRule::NonZero), 7500.0);
+    assert_eq!(filled_area(&opposite_ways, FillRule::EvenOdd), 7500.0);
+
+    // Two squares overlapping by a quarter
+    let mut overlapping = Path::new();
+    square(&mut overlapping, [50.0, 50.0], 100.0, true);
+    square(&mut overlapping, [100.0, 100.0], 100.0, true);
+    assert_eq!(filled_area(&overlapping, FillRule::NonZero), 17500.0);
+    assert_eq!(filled_area(&overlapping, FillRule::EvenOdd), 15000.0);
+}
+
+#[test]
+fn self_intersecting_outlines_fill_by_rule() {
+    let mut star = Path::new();
+    for index in 0..5 {
+        let angle = -std::f32::consts::FRAC_PI_2 + index as f32 * 0.8 * std::f32::consts::PI;
+        star.line_to([64.0 + 60.0 * angle.cos(), 64.0 + 60.0 * angle.sin()]);
+    }
+    star.close();
+
+    let non_zero = CpuRenderer::new().render(&star.fill(FillRule::NonZero, RED), 128, 128, CLEAR_COLOUR);
+    let even_odd = CpuRenderer::new().render(&star.fill(FillRule::EvenOdd, RED), 128, 128, CLEAR_COLOUR);
+    assert_eq!(non_zero.get_pixel(64, 64).0, [255, 0, 0, 255]);
+    assert_eq!(even_odd.get_pixel(64, 64).0, [77, 77, 128, 255]);
+    assert_eq!(even_odd.get_pixel(64, 20).0, [255, 0, 0, 255]);
+}
+
+#[test]
+fn only_the_outline_fades() {
+    let mut path = Path::new();
+    square(&mut path, [50.0, 50.0], 100.0, true);
+    square(&mut path, [100.0, 100.0], 100.0, true);
+    let rings: Vec<Vec<[f32; 2]>> = path.flatten(0.1).into_iter().map(|(points, _)| points).collect();
+
+    let outline_length: f32 = tessellate(&rings, FillRule::NonZero).iter().map(|(vertices, outline)| {
+        (0..3).filter(|edge| outline & (1 << edge) != 0).map(|edge| {
+            let (from, to) = (vertices[edge], vertices[(edge + 1) % 3]);
+            (to[0] - from[0]).hypot(to[1] - from[1])
+        }).sum::<f32>()
+    }).sum();
+    assert_eq!(outline_length, 600.0);
+}
+
+#[test]
+fn translucent_fills_have_no_seams() {
+    let mut circle = Path::new();
+    circle.move_to([64.0, 4.0])
+        .cubic_to([97.0, 4.0], [124.0, 31.0], [124.0, 64.0])
+        .cubic_to([124.0, 97.0], [97.0, 124.0], [64.0, 124.0])
+        .cubic_to([31.0, 124.0], [4.0, 97.0], [4.0, 64.0])
+        .cubic_to([4.0, 31.0], [31.0, 4.0], [64.0, 4.0]);
+    let primitives = circle.fill(FillRule::NonZero, [1.0, 0.0, 0.0, 0.5]);
+    assert!(primitives.len() > 10);
+
+    let image = CpuRenderer::new().render(&primitives, 128, 128, CLEAR_COLOUR);
+    let centre = image.get_pixel(64, 64).0;
+    for y in 20..108 {
+        for x in 20..108 {
+            if (x as f32 + 0.5 - 64.0).hypot(y as f32 + 0.5 - 64.0) < 55.0 {
+                assert_eq!(image.get_pixel(x, y).0, centre, "at {}, {}", x, y);
+            }
+        }
+    }
+}
+
+#[test]
+fn fills_layer_in_order_with_other_primitives() {
+    let mut triangle = Path::new();
+    triangle.move_to([10.0, 10.0]).line_to([110.0, 10.0]).line_to([10.0, 110.0]).close();
+
+    let mut primitives = vec!(RenderPrimitive::rectangle([40.0, 40.0], [60.0, 60.0], BLUE, BLUE, 0.0, [0.0; 4]));
+    primitives.extend(triangle.fill(FillRule::NonZero, RED));
+    primitives.push(RenderPrimitive::circle([30.0, 30.0], 20.0, BLUE, BLUE, 0.0));
+    let image = CpuRenderer::new().render(&primitives, 128, 128, CLEAR_COLOUR);
+
+    assert_eq!(image.get_pixel(30, 30).0, [0, 0, 255, 255]);
+    assert_eq!(image.get_pixel(50, 50).0, [255, 0, 0, 255]);
+    assert_eq!(image.get_pixel(65, 65).0, [0, 0, 255, 255]);
+}
+
+// A path triangle's vertices as the geometry shader decodes them, from the half floats it is given
+fn decoded_vertices(primitive: &RenderPrimitive) -> [[f32; 2]; 3] {
+    let extra_data_1 = Half4::from_f32(primitive.extra_data_1).to_f32();
+    let extra_data_2 = Half4::from_f32(primitive.extra_data_2).to_f32();
+    let fractions = [[extra_data_1[0], extra_data_1[1]], [extra_data_1[2], extra_data_1[3]], [extra_data_2[0], extra_data_2[1]]];
+    fractions.map(|fraction| [
+        primitive.position[0] + fraction[0] * primitive.dimensions[0],
+        primitive.position[1] + fraction[1] * primitive.dimensions[1]
+    ])
+}
+
+fn triangle_area(vertices: [[f32; 2]; 3]) -> f32 {
+    let [a, b, c] = vertices;
+    ((b[0] - a[0]) * (c[1] - a[1]) - (b[1] - a[1]) * (c[0] - a[0])).abs() / 2.0
+}
+
+#[test]
+fn large_paths_are_cut_into_tiles_that_keep_their_vertices() {
+    // Far past where fractions of the whole path stored as half floats lose precision
+    let mut path = Path::new();
+    path.move_to([-30000.0, 20.0]).line_to([90000.0, 40.0]).line_to([100.0, 80000.0]).line_to([-200.0, 60000.0]).close();
+    let rings: Vec<Vec<[f32; 2]>> = path.flatten(0.25).into_iter().map(|(points, _)| points).collect();
+    let triangles = tessellate(&rings, FillRule::NonZero);
+    let primitives = path.fill(FillRule::NonZero, RED);
+    assert!(primitives.len() > triangles.len());
+
+    let mut area = 0.0f64;
+    for primitive in &primitives {
+        assert_eq!(primitive.dimensions, [MAX_PATH_EXTENT; 2]);
+        for vertex in decoded_vertices(primitive) {
+            for axis in 0..2 {
+                let offset = (vertex[axis] - primitive.position[axis]).abs();
+                assert!(offset <= MAX_PATH_EXTENT / 2.0, "{:?} lies outside its tile at {:?}", vertex, primitive.position);
+            }
+        }
+        area += triangle_area(decoded_vertices(primitive)) as f64;
+    }
+    let tessellated_area: f64 = triangles.iter().map(|(vertices, _)| triangle_area(*vertices) as f64).sum();
+    assert!((area - tessellated_area).abs() < 1e-4 * tessellated_area, "tiles cover {} of {}", area, tessellated_area);
+}
+
+#[test]
+fn tiles_of_large_fills_meet_without_seams() {
+    let mut triangle = Path::new();
+    triangle.move_to([-2000.0, -1000.0]).line_to([4000.0, 1000.0]).line_to([500.0, 3000.0]).close();
+    // Looking at the corner where four tiles meet
+    let primitives: Vec<RenderPrimitive> = triangle.fill(FillRule::NonZero, [1.0, 0.0, 0.0, 0.5])
+        .into_iter()
+        .map(|primitive| primitive.transformed(Transform::translation([-960.0, -960.0])))
+        .collect();
+
+    let image = CpuRenderer::new().render(&primitives, 128, 128, CLEAR_COLOUR);
+    let filled = image.get_pixel(0, 0).0;
+    assert_ne!(filled, [77, 77, 128, 255]);
+    assert!(image.pixels().all(|pixel| pixel.0 == filled));
+}
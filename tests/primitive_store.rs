@@ -0,0 +1,196 @@
+use rust_glium::headless::OffscreenRenderer;
+use rust_glium::primitive_store::{DirtyRanges, PrimitiveHandle, PrimitiveStore};
+use rust_glium::primitives::RenderPrimitive;
+
+#[test]
+fn consecutive_indices_form_one_range() {
+    let mut dirty = DirtyRanges::default();
+    for index in 0..100 {
+        dirty.insert(index);
+    }
+    assert_eq!(dirty.take(), vec!(0..100));
+    assert!(dirty.is_empty());
+}
+
+#[test]
+fn ranges_are_sorted_and_merged() {
+    let mut dirty = DirtyRanges::default();
+    for index in [10, 11, 3, 4, 12, 5, 20, 4, 6, 7, 8, 9] {
+        dirty.insert(index);
+    }
+    assert_eq!(dirty.take(), vec!(3..13, 20..21));
+}
+
+#[test]
+fn repeated_indices_are_written_once() {
+    let mut dirty = DirtyRanges::default();
+    dirty.insert(5);
+    dirty.insert(5);
+    dirty.insert(1);
+    dirty.insert(5);
+    assert_eq!(dirty.take(), vec!(1..2, 5..6));
+}
+
+fn square(size: u16) -> RenderPrimitive {
+    let size = size as f32;
+    RenderPrimitive::rectangle([0.0, 0.0], [size, size], [1.0; 4], [1.0; 4], 0.0, [0.0; 4])
+}
+
+#[test]
+fn handles_address_their_primitive() {
+    let mut store = PrimitiveStore::new();
+    let first = store.add(square(1));
+    let second = store.add(square(2));
+
+    assert!(store.update(first, square(3)));
+    assert_eq!(store.get(first), Some(&square(3)));
+    assert_eq!(store.get(second), Some(&square(2)));
+    assert_eq!(store.len(), 2);
+}
+
+#[test]
+fn removed_handles_stay_invalid_after_their_slot_is_reused() {
+    let mut store = PrimitiveStore::new();
+    let removed = store.add(square(1));
+    store.add(square(2));
+    assert_eq!(store.remove(removed), Some(square(1)));
+
+    let added = store.add(square(3));
+    assert_eq!(store.get(removed), None);
+    assert!(!store.update(removed, square(4)));
+    assert_eq!(store.remove(removed), None);
+    assert_eq!(store.get(added), Some(&square(3)));
+}
+
+#[test]
+fn removed_primitives_leave_hidden_gaps_until_compacted() {
+    let mut store = PrimitiveStore::new();
+    let handles: Vec<_> = (1..=4).map(|size| store.add(square(size))).collect();
+    store.remove(handles[1]);
+
+    assert_eq!(store.len(), 3);
+    assert_eq!(store.primitives().len(), 4);
+    assert_eq!(store.primitives()[1].dimensions, [0.0, 0.0]);
+
+    store.compact();
+    assert_eq!(store.primitives(), &[square(1), square(3), square(4)][..]);
+    assert_eq!(store.get(handles[3]), Some(&square(4)));
+    assert!(store.update(handles[2], square(5)));
+    assert_eq!(store.primitives()[1], square(5));
+}
+
+#[test]
+fn removing_from_the_end_shortens_the_draw_range() {
+    let mut store = PrimitiveStore::new();
+    let first = store.add(square(1));
+    let middle = store.add(square(2));
+    let last = store.add(square(3));
+
+    store.remove(middle);
+    store.remove(last);
+    assert_eq!(store.primitives(), &[square(1)][..]);
+
+    store.remove(first);
+    assert!(store.is_empty());
+    assert!(store.primitives().is_empty());
+}
+
+const CLEAR_COLOUR: [f32; 4] = [0.3, 0.3, 0.5, 1.0];
+
+// Overlapping primitives that tell apart their index and draw order
+fn primitive(index: usize) -> RenderPrimitive {
+    let position = [((index * 7) % 128) as f32, ((index * 3 + index / 16) % 128) as f32];
+    let colour = [(index % 3) as f32 / 2.0, (index % 5) as f32 / 4.0, 1.0, 1.0];
+    if index.is_multiple_of(2) {
+        RenderPrimitive::circle(position, 12.0, colour, colour, 0.0)
+    } else {
+        RenderPrimitive::rectangle(position, [10.0, 6.0], colour, colour, 0.0, [0.0; 4])
+    }
+}
+
+#[test]
+#[ignore = "needs an OpenGL 3.3 context"]
+fn edited_stores_render_like_direct_draws() {
+    let mut renderer = OffscreenRenderer::new(128, 128).unwrap();
+    let mut store = PrimitiveStore::new();
+    let mut expected: Vec<_> = (0..300).map(|index| (store.add(primitive(index)), primitive(index))).collect();
+
+    let mut assert_renders_like_direct_draw = |store: &mut PrimitiveStore, expected: &[(PrimitiveHandle, RenderPrimitive)], edit: &str| {
+        let primitives: Vec<RenderPrimitive> = expected.iter().map(|&(_, primitive)| primitive).collect();
+        let stored = renderer.render_store(store, CLEAR_COLOUR).unwrap();
+        let direct = renderer.render(&primitives, CLEAR_COLOUR).unwrap();
+        assert!(stored == direct, "store differs from a direct draw after {}", edit);
+    };
+    assert_renders_like_direct_draw(&mut store, &expected, "adding");
+
+    for index in (0..300).step_by(7) {
+        expected[index].1 = primitive(index + 1000);
+        assert!(store.update(expected[index].0, expected[index].1));
+    }
+    assert_renders_like_direct_draw(&mut store, &expected, "updating");
+
+    for index in (0..expected.len()).rev().step_by(3) {
+        store.remove(expected.remove(index).0);
+    }
+    assert_renders_like_direct_draw(&mut store, &expected, "removing");
+
+    for index in 2000..2050 {
+        expected.push((store.add(primitive(index)), primitive(index)));
+    }
+    assert_renders_like_direct_draw(&mut store, &expected, "adding into reused slots");
+
+    store.compact();
+    assert_renders_like_direct_draw(&mut store, &expected, "compacting");
+}
+
+#[test]
+#[ignore = "needs an OpenGL 3.3 context"]
+fn uploads_write_only_dirty_ranges() {
+    let renderer = OffscreenRenderer::new(16, 16).unwrap();
+    let mut store = PrimitiveStore::new();
+    let handles: Vec<_> = (0..100).map(|index| store.add(primitive(index))).collect();
+
+    assert_eq!(store.upload(renderer.display()), 1);
+    assert_eq!(store.upload(renderer.display()), 0);
+
+    store.update(handles[3], primitive(200));
+    store.update(handles[50], primitive(201));
+    assert_eq!(store.upload(renderer.display()), 2);
+
+    for &handle in &handles[10..13] {
+        store.update(handle, primitive(202));
+    }
+    assert_eq!(store.upload(renderer.display()), 1);
+
+    // Removing from the end only shortens the draw range, from the middle hides one primitive
+    store.remove(handles[99]);
+    assert_eq!(store.upload(renderer.display()), 0);
+    store.remove(handles[20]);
+    store.add(primitive(203));
+    assert_eq!(store.upload(renderer.display()), 2);
+    assert_eq!(store.vertices().unwrap().len(), 100);
+}
+
+#[test]
+#[ignore = "needs an OpenGL 3.3 context"]
+fn stores_grow_past_their_initial_capacity_and_shrink_to_fit() {
+    let mut renderer = OffscreenRenderer::new(128, 128).unwrap();
+    let mut store = PrimitiveStore::new();
+    let handles: Vec<_> = (0..3000).map(|index| store.add(primitive(index))).collect();
+
+    let primitives: Vec<RenderPrimitive> = (0..3000).map(primitive).collect();
+    assert!(renderer.render_store(&mut store, CLEAR_COLOUR).unwrap() == renderer.render(&primitives, CLEAR_COLOUR).unwrap());
+    assert_eq!(store.capacity(), 4096);
+
+    for &handle in &handles[10..] {
+        store.remove(handle);
+    }
+    store.remove(handles[4]);
+    store.shrink_to_fit();
+    assert!(store.update(handles[0], primitive(5000)));
+
+    let primitives: Vec<RenderPrimitive> = [5000, 1, 2, 3, 5, 6, 7, 8, 9].iter().map(|&index| primitive(index)).collect();
+    assert!(renderer.render_store(&mut store, CLEAR_COLOUR).unwrap() == renderer.render(&primitives, CLEAR_COLOUR).unwrap());
+    assert_eq!(store.capacity(), 1024);
+    assert_eq!(store.vertices().unwrap().len(), 9);
+}
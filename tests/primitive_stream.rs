@@ -0,0 +1,67 @@
+use std::time::Duration;
+use rust_glium::headless::OffscreenRenderer;
+use rust_glium::primitive_stream::{PrimitiveStream, StreamStats};
+use rust_glium::primitives::{PackedPrimitive, RenderPrimitive};
+
+#[test]
+fn throughput_is_megabytes_per_second() {
+    let stats = StreamStats { frames: 10, primitives: 1000, bytes: 3_000_000, upload_time: Duration::from_millis(1500) };
+    assert!((stats.throughput() - 2.0).abs() < 1e-9);
+}
+
+#[test]
+fn throughput_without_uploads_is_zero() {
+    assert_eq!(StreamStats::default().throughput(), 0.0);
+}
+
+const CLEAR_COLOUR: [f32; 4] = [0.3, 0.3, 0.5, 1.0];
+
+// A frame's worth of primitives that differ from frame to frame, in number as well as position
+fn frame(frame: usize, count: usize) -> Vec<RenderPrimitive> {
+    (0..count).map(|index| {
+        let position = [((index * 7 + frame * 13) % 128) as f32, ((index * 3 + frame * 29) % 128) as f32];
+        let colour = [(frame % 3) as f32 / 2.0, (index % 5) as f32 / 4.0, 1.0, 1.0];
+        if index % 2 == 0 {
+            RenderPrimitive::circle(position, 6.0, colour, colour, 0.0)
+        } else {
+            RenderPrimitive::rectangle(position, [8.0, 5.0], colour, colour, 0.0, [0.0; 4])
+        }
+    }).collect()
+}
+
+#[test]
+#[ignore = "needs an OpenGL 3.3 context"]
+fn streamed_frames_render_like_direct_draws() {
+    let mut renderer = OffscreenRenderer::new(128, 128).unwrap();
+    let mut stream = PrimitiveStream::new(renderer.display());
+
+    // More frames than buffers in flight, growing past the initial capacity and shrinking again
+    let counts = [10, 200, 3000, 40, 0, 1500, 7];
+    for (index, &count) in counts.iter().enumerate() {
+        let primitives = frame(index, count);
+        let streamed = renderer.render_stream(&mut stream, &primitives, CLEAR_COLOUR).unwrap();
+        let direct = renderer.render(&primitives, CLEAR_COLOUR).unwrap();
+        assert!(streamed == direct, "frame {} of {} primitives differs from a direct draw", index, count);
+    }
+
+    let stats = stream.stats();
+    let total = counts.iter().sum::<usize>() as u64;
+    assert_eq!((stats.frames, stats.primitives), (counts.len() as u64, total));
+    assert_eq!(stats.bytes, total * std::mem::size_of::<PackedPrimitive>() as u64);
+}
+
+#[test]
+#[ignore = "needs an OpenGL 3.3 context"]
+fn writes_cover_only_this_frames_primitives() {
+    let renderer = OffscreenRenderer::new(16, 16).unwrap();
+    let mut stream = PrimitiveStream::new(renderer.display());
+
+    for count in [5, 2000, 3, 0] {
+        stream.write(&frame(0, count));
+        assert_eq!(stream.vertices().len(), count);
+        stream.end_frame();
+    }
+
+    stream.reset_stats();
+    assert_eq!(stream.stats(), StreamStats::default());
+}
@@ -0,0 +1,149 @@
+use std::f32::consts::FRAC_PI_2;
+use rust_glium::primitives::{Half4, LineCap, PackedPrimitive, RenderPrimitive, MAX_CURVE_EXTENT, MAX_PATH_EXTENT};
+use rust_glium::transform::Transform;
+
+#[test]
+fn packed_primitives_are_smaller() {
+    // Float position and dimensions, colours, kind and glyph, payloads, linear transform
+    assert_eq!(std::mem::size_of::<PackedPrimitive>(), 16 + 8 + 4 + 16 + 16);
+    assert!(std::mem::size_of::<PackedPrimitive>() < std::mem::size_of::<RenderPrimitive>());
+
+    // The vertex attributes read each field where it sits
+    let offsets = [
+        ("position", std::mem::offset_of!(PackedPrimitive, position)),
+        ("dimensions", std::mem::offset_of!(PackedPrimitive, dimensions)),
+        ("inner_colour", std::mem::offset_of!(PackedPrimitive, inner_colour)),
+        ("outer_colour", std::mem::offset_of!(PackedPrimitive, outer_colour)),
+        ("kind_and_glyph", std::mem::offset_of!(PackedPrimitive, kind_and_glyph)),
+        ("extra_data_1", std::mem::offset_of!(PackedPrimitive, extra_data_1)),
+        ("extra_data_2", std::mem::offset_of!(PackedPrimitive, extra_data_2)),
+        ("linear_transform", std::mem::offset_of!(PackedPrimitive, linear_transform))
+    ];
+    let bindings = <PackedPrimitive as glium::Vertex>::build_bindings();
+    let attributes: Vec<(&str, usize)> = bindings.iter().map(|(name, offset, _, _)| (name.as_ref(), *offset)).collect();
+    assert_eq!(attributes, offsets.to_vec());
+}
+
+#[test]
+fn kind_and_glyph_share_one_word() {
+    let packed = RenderPrimitive::text([10.0, 20.0], [30.0, 40.0], [1.0; 4], 0x1234).pack();
+    assert_eq!(packed.kind_and_glyph, 0x0002_1234);
+    assert_eq!(packed.unpack().identification, [2, 0x1234]);
+}
+
+#[test]
+fn colours_round_to_eight_bits() {
+    let primitive = RenderPrimitive::circle([0.0, 0.0], 10.0, [1.0, 0.5, 0.0, 1.0], [2.0, -1.0, 0.25, 0.0], 0.0);
+    let packed = primitive.pack();
+    assert_eq!(packed.inner_colour, [255, 128, 0, 255]);
+    assert_eq!(packed.outer_colour, [255, 0, 64, 0]);
+    assert_eq!(packed.unpack().inner_colour, [1.0, 128.0 / 255.0, 0.0, 1.0]);
+}
+
+#[test]
+fn payloads_survive_as_half_floats() {
+    let primitive = RenderPrimitive::rectangle([5.5, -6.25], [7.0, 8.0], [1.0; 4], [1.0; 4], 8.0, [0.0, 0.1, 0.25, 0.4]);
+    let unpacked = primitive.pack().unpack();
+    assert_eq!(unpacked.position, [5.5, -6.25]);
+    assert_eq!(unpacked.dimensions, [7.0, 8.0]);
+    assert_eq!(unpacked.extra_data_1, [8.0, 0.0, 0.0, 0.0]);
+    for (original, decoded) in primitive.extra_data_2.iter().zip(unpacked.extra_data_2.iter()) {
+        assert!((original - decoded).abs() < 0.001);
+    }
+}
+
+#[test]
+fn half_floats_cover_special_values() {
+    let half = Half4::from_f32([65504.0, 1.0e6, -0.0, 1.0e-7]);
+    assert_eq!(half.0, [0x7bff, 0x7c00, 0x8000, 0x0002]);
+    assert_eq!(half.to_f32()[0], 65504.0);
+    assert_eq!(half.to_f32()[1], f32::INFINITY);
+    assert_eq!(Half4::from_f32([1.0, -2.0, 0.5, 2.0f32.powi(-14)]).0, [0x3c00, 0xc000, 0x3800, 0x0400]);
+    assert!(Half4([0x7e00, 0, 0, 0]).to_f32()[0].is_nan());
+}
+
+#[test]
+fn shrinking_stops_at_zero() {
+    let mut primitive = RenderPrimitive::rectangle([0.0, 0.0], [10.0, 4.0], [1.0; 4], [1.0; 4], 0.0, [0.0; 4]);
+    primitive.expand_dimensions(-6.0);
+    assert_eq!(primitive.dimensions, [4.0, 0.0]);
+    primitive.expand_dimensions(1.5);
+    assert_eq!(primitive.dimensions, [5.5, 1.5]);
+}
+
+#[test]
+fn checked_resizing_rejects_negative_dimensions() {
+    let primitive = RenderPrimitive::circle([0.0, 0.0], 10.0, [1.0; 4], [1.0; 4], 0.0);
+    assert_eq!(primitive.checked_expand_dimensions(-2.5).map(|resized| resized.dimensions), Some([7.5, 7.5]));
+    assert_eq!(primitive.checked_expand_dimensions(-10.5), None);
+    assert_eq!(primitive.checked_expand_dimensions(f32::INFINITY), None);
+}
+
+#[test]
+fn packing_moves_the_translation_into_the_position() {
+    let primitive = RenderPrimitive::circle([10.0, 20.0], 4.0, [1.0; 4], [1.0; 4], 0.0)
+        .transformed(Transform::translation([5.0, -5.0]))
+        .rotated(FRAC_PI_2);
+    let packed = primitive.pack();
+
+    assert_eq!(packed.position, [15.0, 15.0]);
+    assert!((packed.linear_transform[0] - 0.0).abs() < 1e-6 && (packed.linear_transform[1] - 1.0).abs() < 1e-6);
+    let unpacked = packed.unpack();
+    let corner = unpacked.transform.apply([17.0, 15.0]);
+    assert!((corner[0] - 15.0).abs() < 1e-5 && (corner[1] - 17.0).abs() < 1e-5);
+}
+
+#[test]
+fn lines_are_boxes_turned_along_them() {
+    let line = RenderPrimitive::line([10.0, 10.0], [10.0, 50.0], 4.0, [1.0; 4], LineCap::Square);
+    assert_eq!(line.identification[0], 1);
+    assert_eq!(line.position, [10.0, 30.0]);
+    assert_eq!(line.dimensions, [44.0, 4.0]);
+    let end = line.transform.apply([32.0, 30.0]);
+    assert!((end[0] - 10.0).abs() < 1e-4 && (end[1] - 52.0).abs() < 1e-4);
+}
+
+#[test]
+fn slices_store_their_middle_and_half_angle() {
+    let pie = RenderPrimitive::pie([0.0, 0.0], 10.0, -FRAC_PI_2, 0.0, [1.0; 4], [1.0; 4], 0.0);
+    let half = std::f32::consts::FRAC_PI_4;
+    let expected = [(-half).cos(), (-half).sin(), half.cos(), half.sin()];
+    for (stored, expected) in pie.extra_data_2.iter().zip(expected.iter()) {
+        assert!((stored - expected).abs() < 1e-6);
+    }
+
+    let full = RenderPrimitive::arc([0.0, 0.0], 10.0, 1.0, 1.0 + 7.0, 2.0, [1.0; 4], [1.0; 4], 0.0);
+    assert!((full.extra_data_2[2] + 1.0).abs() < 1e-6);
+    assert_eq!(full.extra_data_1[1], 2.0);
+}
+
+#[test]
+fn triangles_are_bounded_by_their_quad() {
+    let triangle = RenderPrimitive::triangle([[0.0, 0.0], [40.0, 10.0], [20.0, 30.0]], [1.0; 4], [1.0; 4], 0.0);
+    assert_eq!(triangle.position, [20.0, 15.0]);
+    assert_eq!(triangle.dimensions, [40.0, 30.0]);
+    assert_eq!(triangle.extra_data_2, [-0.5, -0.5, 0.5, -1.0 / 6.0]);
+    assert_eq!(&triangle.extra_data_1[1..3], &[0.0, 0.5]);
+}
+
+#[test]
+fn curves_store_each_cap_and_fit_one_quad() {
+    let curve = RenderPrimitive::quadratic_bezier([0.0, 0.0], [50.0, 80.0], [100.0, 0.0], 4.0, [1.0; 4], LineCap::Round, LineCap::Square).unwrap();
+    assert_eq!(curve.extra_data_1[3], 2.0 + 4.0 * 1.0);
+    // Square caps reach past the end points diagonally
+    assert!((curve.dimensions[0] - (100.0 + 4.0 * std::f32::consts::SQRT_2)).abs() < 1e-4);
+
+    assert!(RenderPrimitive::quadratic_bezier([0.0, 0.0], [500.0, 800.0], [MAX_CURVE_EXTENT - 4.0, 0.0], 4.0, [1.0; 4], LineCap::Butt, LineCap::Butt).is_some());
+    assert!(RenderPrimitive::quadratic_bezier([0.0, 0.0], [500.0, 800.0], [MAX_CURVE_EXTENT, 0.0], 4.0, [1.0; 4], LineCap::Butt, LineCap::Butt).is_none());
+}
+
+#[test]
+fn path_triangles_fit_one_quad() {
+    let vertices = [[-100.0, -100.0], [100.0, -100.0], [0.0, 100.0]];
+    let triangle = RenderPrimitive::path_triangle([0.0, 0.0], [200.0, 200.0], vertices, 0b101, [1.0; 4]).unwrap();
+    assert_eq!(triangle.extra_data_1, [-0.5, -0.5, 0.5, -0.5]);
+    assert_eq!(triangle.extra_data_2, [0.0, 0.5, 5.0, 0.0]);
+
+    assert!(RenderPrimitive::path_triangle([0.0, 0.0], [MAX_PATH_EXTENT, 10.0], vertices, 0, [1.0; 4]).is_some());
+    assert!(RenderPrimitive::path_triangle([0.0, 0.0], [10.0, MAX_PATH_EXTENT + 1.0], vertices, 0, [1.0; 4]).is_none());
+}
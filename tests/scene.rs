@@ -0,0 +1,57 @@
+use std::f32::consts::FRAC_PI_2;
+use rust_glium::primitives::RenderPrimitive;
+use rust_glium::scene::Scene;
+use rust_glium::transform::Transform;
+
+const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+fn dot(position: [f32; 2]) -> RenderPrimitive {
+    RenderPrimitive::circle(position, 2.0, WHITE, WHITE, 0.0)
+}
+
+fn drawn_at(primitive: &RenderPrimitive) -> [f32; 2] {
+    primitive.transform.apply(primitive.position)
+}
+
+fn assert_near(actual: [f32; 2], expected: [f32; 2]) {
+    assert!((actual[0] - expected[0]).abs() < 1e-4 && (actual[1] - expected[1]).abs() < 1e-4, "{:?} != {:?}", actual, expected);
+}
+
+#[test]
+fn primitives_outside_groups_are_untouched() {
+    let mut scene = Scene::new();
+    scene.add(dot([1.0, 2.0]));
+    assert_eq!(scene.primitives(), &[dot([1.0, 2.0])][..]);
+}
+
+#[test]
+fn moving_a_group_moves_its_children() {
+    let mut scene = Scene::new();
+    scene.push_transform(Transform::translation([100.0, 50.0]));
+    scene.add(dot([1.0, 2.0]));
+    scene.extend(vec!(dot([3.0, 4.0])));
+    assert!(scene.pop_transform());
+    scene.add(dot([5.0, 6.0]));
+
+    let positions: Vec<[f32; 2]> = scene.primitives().iter().map(drawn_at).collect();
+    assert_eq!(positions, vec!([101.0, 52.0], [103.0, 54.0], [5.0, 6.0]));
+}
+
+#[test]
+fn nested_transforms_apply_innermost_first() {
+    let mut scene = Scene::new();
+    scene.with_transform(Transform::translation([100.0, 0.0]), |scene| {
+        scene.with_transform(Transform::rotation(FRAC_PI_2), |scene| {
+            scene.with_transform(Transform::scale([2.0, 2.0]), |scene| scene.add(dot([10.0, 0.0])));
+        });
+    });
+
+    assert_near(drawn_at(&scene.primitives()[0]), [100.0, 20.0]);
+    assert_eq!(scene.current_transform(), Transform::IDENTITY);
+}
+
+#[test]
+fn popping_an_empty_stack_reports_it() {
+    let mut scene = Scene::new();
+    assert!(!scene.pop_transform());
+}
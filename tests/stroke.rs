@@ -0,0 +1,79 @@
+use rust_glium::primitives::LineCap;
+use rust_glium::stroke::{
+    flatten_cubic, flatten_quadratic, stroke_cubic, stroke_polyline, stroke_quadratic, StrokeStyle, DEFAULT_TOLERANCE
+};
+
+const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+fn distance_to_polyline(point: [f32; 2], polyline: &[[f32; 2]]) -> f32 {
+    polyline.windows(2).map(|line| {
+        let edge = [line[1][0] - line[0][0], line[1][1] - line[0][1]];
+        let offset = [point[0] - line[0][0], point[1] - line[0][1]];
+        let along = ((offset[0] * edge[0] + offset[1] * edge[1]) / (edge[0] * edge[0] + edge[1] * edge[1])).clamp(0.0, 1.0);
+        (offset[0] - edge[0] * along).hypot(offset[1] - edge[1] * along)
+    }).fold(f32::INFINITY, f32::min)
+}
+
+#[test]
+fn flattened_curves_stay_within_the_tolerance() {
+    let (from, control, to) = ([0.0, 0.0], [200.0, 400.0], [400.0, 0.0]);
+    let points = flatten_quadratic(from, control, to, DEFAULT_TOLERANCE);
+    assert_eq!(points.first(), Some(&from));
+    assert_eq!(points.last(), Some(&to));
+    for step in 0..=1000 {
+        let t = step as f32 / 1000.0;
+        let u = 1.0 - t;
+        let on_curve = [2.0 * u * t * control[0] + t * t * to[0], 2.0 * u * t * control[1]];
+        assert!(distance_to_polyline(on_curve, &points) <= DEFAULT_TOLERANCE + 0.01);
+    }
+
+    let controls = [[0.0, 0.0], [0.0, 300.0], [400.0, -300.0], [400.0, 0.0]];
+    let points = flatten_cubic(controls[0], controls[1], controls[2], controls[3], DEFAULT_TOLERANCE);
+    for step in 0..=1000 {
+        let t = step as f32 / 1000.0;
+        let u = 1.0 - t;
+        let weights = [u * u * u, 3.0 * u * u * t, 3.0 * u * t * t, t * t * t];
+        let on_curve = [
+            (0..4).map(|index| weights[index] * controls[index][0]).sum(),
+            (0..4).map(|index| weights[index] * controls[index][1]).sum()
+        ];
+        assert!(distance_to_polyline(on_curve, &points) <= DEFAULT_TOLERANCE + 0.01);
+    }
+}
+
+#[test]
+fn straight_curves_flatten_to_one_line() {
+    assert_eq!(flatten_quadratic([0.0, 0.0], [50.0, 0.0], [100.0, 0.0], DEFAULT_TOLERANCE).len(), 2);
+    assert_eq!(flatten_cubic([0.0, 0.0], [30.0, 0.0], [60.0, 0.0], [90.0, 0.0], DEFAULT_TOLERANCE).len(), 2);
+}
+
+#[test]
+fn curves_draw_as_one_primitive_where_a_quad_can_hold_them() {
+    let style = StrokeStyle::new(4.0, WHITE);
+    let curve = stroke_quadratic([0.0, 0.0], [50.0, 80.0], [100.0, 0.0], &style);
+    assert_eq!(curve.len(), 1);
+    assert_eq!(curve[0].identification[0], 7);
+
+    // Too large for the points to stay accurate in half floats
+    assert!(stroke_quadratic([0.0, 0.0], [1000.0, 1600.0], [2000.0, 0.0], &style).len() > 1);
+    // Too straight for the curve solver
+    assert!(stroke_quadratic([0.0, 0.0], [50.0, 0.1], [100.0, 0.0], &style).iter().all(|line| line.identification[0] == 8));
+
+    // A cubic that is really a quadratic needs no flattening, an S bend does
+    assert_eq!(stroke_cubic([0.0, 0.0], [100.0 / 3.0, 160.0 / 3.0], [200.0 / 3.0, 160.0 / 3.0], [100.0, 0.0], &style).len(), 1);
+    assert!(stroke_cubic([0.0, 0.0], [0.0, 100.0], [100.0, -100.0], [100.0, 0.0], &style).len() > 1);
+}
+
+#[test]
+fn polylines_draw_one_primitive_per_line() {
+    let style = StrokeStyle::new(4.0, WHITE);
+    let square = [[0.0, 0.0], [10.0, 0.0], [10.0, 10.0], [0.0, 10.0]];
+    assert_eq!(stroke_polyline(&square, false, &style).len(), 3);
+    assert_eq!(stroke_polyline(&square, true, &style).len(), 4);
+    assert_eq!(stroke_polyline(&[[0.0, 0.0], [0.0, 0.0], [10.0, 0.0], [10.0, 0.0]], false, &style).len(), 1);
+
+    // A lone point only shows its caps
+    assert!(stroke_polyline(&[[5.0, 5.0]], false, &style).is_empty());
+    let round = StrokeStyle { cap: LineCap::Round, ..style };
+    assert_eq!(stroke_polyline(&[[5.0, 5.0]], false, &round).len(), 1);
+}
@@ -0,0 +1,105 @@
+use rust_glium::cpu_renderer::CpuRenderer;
+use rust_glium::path::PathSegment;
+use rust_glium::svg::{parse_path_data, SvgError, SvgIcon};
+
+const CLEAR_COLOUR: [f32; 4] = [0.3, 0.3, 0.5, 1.0];
+const CLEAR: [u8; 4] = [77, 77, 128, 255];
+const TINT: [f32; 4] = [0.0, 1.0, 0.0, 1.0];
+
+fn pixel(image: &image::RgbaImage, x: u32, y: u32) -> [u8; 4] {
+    image.get_pixel(x, y).0
+}
+
+#[test]
+fn documents_need_an_svg_root_and_a_size() {
+    assert!(matches!(SvgIcon::load("<svg"), Err(SvgError::Xml(_))));
+    assert!(matches!(SvgIcon::load("<html/>"), Err(SvgError::NotSvg)));
+    assert!(matches!(SvgIcon::load("<svg/>"), Err(SvgError::MissingSize)));
+    assert!(matches!(SvgIcon::open("missing.svg"), Err(SvgError::Io(_))));
+
+    let sized = SvgIcon::load(r#"<svg xmlns="http://www.w3.org/2000/svg" width="32px" height="16"/>"#).unwrap();
+    assert_eq!(sized.view_box(), [0.0, 0.0, 32.0, 16.0]);
+    let boxed = SvgIcon::load(r#"<svg width="100" height="100" viewBox="-4 -4, 24 24"/>"#).unwrap();
+    assert_eq!(boxed.view_box(), [-4.0, -4.0, 24.0, 24.0]);
+}
+
+#[test]
+fn path_data_reads_every_command() {
+    let path = parse_path_data("M.5.5-1e1,2m1 1 2 2h3V0zq1 0 1 1t1 1c0 1 1 1 1 0s1-1 1 0L0 0 7");
+    assert_eq!(path.segments(), &[
+        PathSegment::MoveTo([0.5, 0.5]),
+        PathSegment::LineTo([-10.0, 2.0]),
+        PathSegment::MoveTo([-9.0, 3.0]),
+        PathSegment::LineTo([-7.0, 5.0]),
+        PathSegment::LineTo([-4.0, 5.0]),
+        PathSegment::LineTo([-4.0, 0.0]),
+        PathSegment::Close,
+        // Back at the start of the subpath, and smooth curves reflect the last control point
+        PathSegment::QuadTo([-8.0, 3.0], [-8.0, 4.0]),
+        PathSegment::QuadTo([-8.0, 5.0], [-7.0, 5.0]),
+        PathSegment::CubicTo([-7.0, 6.0], [-6.0, 6.0], [-6.0, 5.0]),
+        PathSegment::CubicTo([-6.0, 4.0], [-5.0, 4.0], [-5.0, 5.0]),
+        PathSegment::LineTo([0.0, 0.0])
+    ]);
+
+    // Arcs become quarter turns or less of cubic curves, ending exactly where they are told to
+    let arc = parse_path_data("M0 10 A10 10 0 1 1 20 10 a 10 10 0 0 1 -10 10");
+    let ends: Vec<[f32; 2]> = arc.segments().iter().filter_map(|segment| match *segment {
+        PathSegment::CubicTo(_, _, point) => Some(point),
+        _ => None
+    }).collect();
+    assert_eq!(ends.len(), 3);
+    assert!((ends[0][0] - 10.0).abs() < 1e-4 && (ends[0][1] - 0.0).abs() < 1e-4);
+    assert_eq!(&ends[1..], &[[20.0, 10.0], [10.0, 20.0]]);
+}
+
+#[test]
+fn shapes_keep_their_styles_and_transforms() {
+    let icon = SvgIcon::load(r##"
+        <svg viewBox="0 0 24 24" fill="none" stroke="currentColor" stroke-width="2">
+            <g transform="translate(2 0) scale(2)" style="fill: #f00; stroke: none" opacity="0.5">
+                <rect width="4" height="3" rx="1"/>
+            </g>
+            <circle cx="12" cy="12" r="5"/>
+            <ellipse cx="12" cy="12" rx="5" ry="3" stroke-width="1" stroke-opacity="0.5" fill="rgb(0, 0, 255)" fill-opacity="50%"/>
+        </svg>"##).unwrap();
+    let primitives = icon.primitives([100.0, 0.0], [48.0, 24.0], TINT);
+
+    let kinds: Vec<u16> = primitives.iter().map(|primitive| primitive.identification[0]).collect();
+    assert_eq!(kinds, vec!(1, 0, 3, 3));
+
+    // Fitted to the height, centred across the width, then moved by the group
+    let rect = &primitives[0];
+    let top_left = [rect.position[0] - rect.dimensions[0] / 2.0, rect.position[1] - rect.dimensions[1] / 2.0];
+    assert_eq!(rect.transform.apply(top_left), [114.0, 0.0]);
+    assert_eq!(rect.transform.apply_vector([4.0, 3.0]), [8.0, 6.0]);
+    assert_eq!(rect.inner_colour, [1.0, 0.0, 0.0, 0.5]);
+    assert_eq!(rect.extra_data_1[0], 0.0);
+
+    // Strokes straddle the outline and unfilled shapes are clear inside
+    let circle = &primitives[1];
+    assert_eq!((circle.position, circle.dimensions, circle.extra_data_1[0]), ([12.0, 12.0], [12.0, 12.0], 2.0));
+    assert_eq!((circle.inner_colour[3], circle.outer_colour), (0.0, TINT));
+
+    // A translucent stroke goes over a separate fill that shows through its inner half
+    assert_eq!(primitives[2].inner_colour, [0.0, 0.0, 1.0, 0.5]);
+    assert_eq!(primitives[2].dimensions, [10.0, 6.0]);
+    assert_eq!(primitives[3].dimensions, [11.0, 7.0]);
+    assert_eq!(primitives[3].outer_colour, [0.0, 1.0, 0.0, 0.5]);
+}
+
+#[test]
+fn icons_render_with_their_paths() {
+    let icon = SvgIcon::load(r##"
+        <svg viewBox="0 0 32 32">
+            <path fill-rule="evenodd" fill="red" d="M16 2a14 14 0 1 1 0 28a14 14 0 1 1 0-28zM16 8a8 8 0 1 0 0 16a8 8 0 1 0 0-16z"/>
+            <polyline points="10 16 14 20 22 12" fill="none" stroke="currentColor" stroke-width="2" stroke-linejoin="round"/>
+        </svg>"##).unwrap();
+    let image = CpuRenderer::new().render(&icon.primitives([0.0, 0.0], [64.0, 64.0], TINT), 64, 64, CLEAR_COLOUR);
+
+    assert_eq!(pixel(&image, 32, 8), [255, 0, 0, 255]);
+    assert_eq!(pixel(&image, 32, 21), CLEAR);
+    assert_eq!(pixel(&image, 2, 2), CLEAR);
+    // The check mark's corner, drawn at twice the icon's size
+    assert_eq!(pixel(&image, 28, 39), [0, 255, 0, 255]);
+}
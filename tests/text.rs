@@ -0,0 +1,138 @@
+use rust_glium::font::{Font, FontAtlas, FontError, GlyphStrip};
+use rust_glium::text::layout_text;
+
+const BLACK: [f32; 4] = [0.0, 0.0, 0.0, 1.0];
+
+fn segoe_ui() -> Font {
+    FontAtlas::segoe_ui().font
+}
+
+fn glyph_index(font: &Font, character: char) -> u16 {
+    font.glyph(character).and_then(|glyph| glyph.glyph_index).unwrap()
+}
+
+#[test]
+fn maps_characters_to_glyph_indices() {
+    let font = segoe_ui();
+    let primitives = layout_text("AC", &font, 32.0, [0.0, 0.0], BLACK);
+
+    let glyph_indices: Vec<u16> = primitives.iter().map(|primitive| primitive.identification[1]).collect();
+    assert_eq!(glyph_indices, vec!(glyph_index(&font, 'A'), glyph_index(&font, 'C')));
+}
+
+#[test]
+fn advances_scale_with_size() {
+    let font = segoe_ui();
+    let small = layout_text("HH", &font, 16.0, [0.0, 0.0], BLACK);
+    let large = layout_text("HH", &font, 32.0, [0.0, 0.0], BLACK);
+
+    let small_advance = small[1].position[0] - small[0].position[0];
+    let large_advance = large[1].position[0] - large[0].position[0];
+    assert!(small_advance > 0.0);
+    assert!((large_advance - small_advance * 2.0).abs() < 0.001);
+}
+
+#[test]
+fn spaces_advance_without_primitives() {
+    let font = segoe_ui();
+    let primitives = layout_text("H H", &font, 32.0, [0.0, 0.0], BLACK);
+    let without_space = layout_text("HH", &font, 32.0, [0.0, 0.0], BLACK);
+
+    assert_eq!(primitives.len(), 2);
+    assert!(primitives[1].position[0] > without_space[1].position[0]);
+}
+
+#[test]
+fn newlines_return_to_the_origin_one_line_down() {
+    let font = segoe_ui();
+    let primitives = layout_text("H\nH", &font, 32.0, [100.0, 100.0], BLACK);
+
+    assert_eq!(primitives[0].position[0], primitives[1].position[0]);
+    let line_height = primitives[1].position[1] - primitives[0].position[1];
+    assert!((line_height - font.line_height * 32.0).abs() < 0.001);
+}
+
+#[test]
+fn unknown_characters_fall_back_to_question_mark() {
+    let font = segoe_ui();
+    let primitives = layout_text("\u{2603}", &font, 32.0, [0.0, 0.0], BLACK);
+
+    assert_eq!(primitives.len(), 1);
+    assert_eq!(primitives[0].identification[1], glyph_index(&font, '?'));
+}
+
+#[test]
+fn dejavu_sans_covers_latin_1_with_each_glyphs_metrics() {
+    let font = FontAtlas::dejavu_sans().font;
+    let narrow = font.glyph('i').unwrap();
+    let wide = font.glyph('W').unwrap();
+    assert!(narrow.advance < wide.advance);
+    assert_ne!(narrow.plane_bounds, wide.plane_bounds);
+
+    let accented = font.glyph('é').unwrap();
+    assert!(accented.glyph_index.is_some());
+    assert!(accented.plane_bounds.unwrap().top > font.glyph('e').unwrap().plane_bounds.unwrap().top);
+    assert!(font.glyph('\u{ff}').is_some());
+}
+
+#[test]
+fn segoe_ui_measures_each_basic_latin_glyph() {
+    let font = segoe_ui();
+    assert!(font.glyph('i').unwrap().advance < font.glyph('H').unwrap().advance);
+    assert!(font.glyph('H').unwrap().advance < font.glyph('W').unwrap().advance);
+    assert!(font.glyph(' ').unwrap().glyph_index.is_none());
+    assert!(('!'..='~').all(|character| font.glyph(character).and_then(|glyph| glyph.glyph_index).is_some()));
+}
+
+#[test]
+fn glyph_strips_measure_advances_from_their_outlines() {
+    // Two 16 pixel cells, the first inked over columns 4 to 9 and the second blank
+    let mut strip = image::RgbaImage::from_pixel(16, 32, image::Rgba([0, 0, 0, 255]));
+    for y in 2..12 {
+        for x in 4..10 {
+            strip.put_pixel(x, y, image::Rgba([255, 255, 255, 255]));
+        }
+    }
+    let glyph_strip = GlyphStrip {
+        cell_size: 16,
+        pixels_per_em: 8.0,
+        origin: [2.0, 12.0],
+        cells: vec!(('A', 0), (' ', 1)),
+        ascender: 1.0,
+        descender: -0.25,
+        line_height: 1.25,
+        blank_advance: 0.5
+    };
+    let atlas = FontAtlas::load(&glyph_strip.metadata(&strip), &strip).unwrap();
+
+    // Two pixels of bearing either side of six pixels of outline
+    let inked = atlas.font.glyph('A').unwrap();
+    assert_eq!(inked.advance, 1.25);
+    let plane_bounds = inked.plane_bounds.unwrap();
+    assert_eq!((plane_bounds.left, plane_bounds.top), (-0.25, 1.5));
+    assert_eq!(atlas.font.glyph(' ').unwrap().advance, 0.5);
+    assert_eq!(atlas.layers.len(), 1);
+    assert_eq!(atlas.layers[0].get_pixel(5, 5).0, [255, 255, 255, 255]);
+}
+
+fn metadata(size: &str, atlas_bounds: &str) -> String {
+    format!(
+        r#"{{"atlas": {{"size": {}, "height": 16}}, "metrics": {{"lineHeight": 1.2, "ascender": 1.0, "descender": -0.2}},
+            "glyphs": [{{"unicode": 65, "advance": 0.5, "planeBounds": {{"left": 0, "bottom": 0, "right": 0.5, "top": 1}}, "atlasBounds": {}}}]}}"#,
+        size, atlas_bounds
+    )
+}
+
+#[test]
+fn atlases_reject_sizes_and_cells_that_scale_glyphs_by_infinity() {
+    let atlas_image = image::RgbaImage::new(16, 16);
+    let cell = r#"{"left": 0, "bottom": 16, "right": 8, "top": 0}"#;
+    assert!(FontAtlas::load(&metadata("16", cell), &atlas_image).is_ok());
+
+    for size in ["0", "-16"] {
+        assert!(matches!(FontAtlas::load(&metadata(size, cell), &atlas_image), Err(FontError::AtlasSize(_))));
+    }
+    let empty_cell = r#"{"left": 8, "bottom": 16, "right": 8, "top": 0}"#;
+    assert!(matches!(FontAtlas::load(&metadata("16", empty_cell), &atlas_image), Err(FontError::EmptyGlyphCell(65))));
+    assert!(matches!(FontAtlas::load("{}", &atlas_image), Err(FontError::Metadata(_))));
+}
@@ -0,0 +1,50 @@
+use std::f32::consts::FRAC_PI_2;
+use rust_glium::transform::Transform;
+
+fn assert_near(actual: [f32; 2], expected: [f32; 2]) {
+    assert!((actual[0] - expected[0]).abs() < 1e-5 && (actual[1] - expected[1]).abs() < 1e-5, "{:?} != {:?}", actual, expected);
+}
+
+#[test]
+fn rotation_turns_clockwise_on_screen() {
+    assert_near(Transform::rotation(FRAC_PI_2).apply([1.0, 0.0]), [0.0, 1.0]);
+}
+
+#[test]
+fn transforms_apply_in_order() {
+    let transform = Transform::scale([2.0, 3.0]).then(&Transform::translation([10.0, 20.0]));
+    assert_near(transform.apply([1.0, 1.0]), [12.0, 23.0]);
+}
+
+#[test]
+fn pivots_stay_in_place() {
+    let transform = Transform::rotation(1.0).around([30.0, 40.0]);
+    assert_near(transform.apply([30.0, 40.0]), [30.0, 40.0]);
+}
+
+#[test]
+fn rows_match_a_two_by_three_matrix() {
+    let transform = Transform::from_rows([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    assert_near(transform.apply([1.0, 1.0]), [6.0, 15.0]);
+}
+
+#[test]
+fn inverse_undoes_the_transform() {
+    let transform = Transform::rotation(0.3).then(&Transform::scale([2.0, 0.5])).then(&Transform::translation([7.0, -3.0]));
+    let inverse = transform.inverse().unwrap();
+    assert_near(inverse.apply(transform.apply([11.0, 13.0])), [11.0, 13.0]);
+    assert_eq!(Transform::scale([0.0, 1.0]).inverse(), None);
+}
+
+#[test]
+fn mat3_columns_map_points_like_apply() {
+    let transform = Transform::from_rows([[1.0, 2.0, 3.0], [4.0, 5.0, 6.0]]);
+    let columns = transform.to_mat3();
+    let point = [7.0, 8.0, 1.0];
+    let mapped = [
+        columns[0][0] * point[0] + columns[1][0] * point[1] + columns[2][0] * point[2],
+        columns[0][1] * point[0] + columns[1][1] * point[1] + columns[2][1] * point[2]
+    ];
+    assert_near(mapped, transform.apply([7.0, 8.0]));
+    assert_eq!([columns[0][2], columns[1][2], columns[2][2]], [0.0, 0.0, 1.0]);
+}
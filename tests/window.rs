@@ -0,0 +1,32 @@
+use glium::glutin::dpi::PhysicalSize;
+use glium::glutin::event::WindowEvent;
+use rust_glium::window::WindowMetrics;
+
+#[test]
+fn resizes_update_the_resolution() {
+    let mut metrics = WindowMetrics::new((800, 600), 1.0);
+    assert!(metrics.update(&WindowEvent::Resized(PhysicalSize::new(1024, 768))));
+    assert_eq!(metrics.resolution(), [1024.0, 768.0]);
+    assert_eq!(metrics.scale_factor, 1.0);
+}
+
+#[test]
+fn scale_factor_changes_carry_the_new_size() {
+    let mut metrics = WindowMetrics::new((800, 600), 1.0);
+    let mut new_inner_size = PhysicalSize::new(1600, 1200);
+    assert!(metrics.update(&WindowEvent::ScaleFactorChanged { scale_factor: 2.0, new_inner_size: &mut new_inner_size }));
+    assert_eq!(metrics, WindowMetrics::new((1600, 1200), 2.0));
+}
+
+#[test]
+fn unchanged_sizes_and_other_events_need_no_redraw() {
+    let mut metrics = WindowMetrics::new((800, 600), 1.0);
+    assert!(!metrics.update(&WindowEvent::Resized(PhysicalSize::new(800, 600))));
+    assert!(!metrics.update(&WindowEvent::Focused(true)));
+    assert_eq!(metrics, WindowMetrics::new((800, 600), 1.0));
+}
+
+#[test]
+fn logical_size_divides_out_the_scale_factor() {
+    assert_eq!(WindowMetrics::new((1600, 1200), 2.0).logical_size(), [800.0, 600.0]);
+}